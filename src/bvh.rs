@@ -0,0 +1,342 @@
+use crate::{
+    math::{Aabb, Vec3},
+    object::Ray,
+};
+use std::ops::ControlFlow;
+
+/// Number of buckets primitives are binned into when looking for the best split
+const BINS: usize = 12;
+/// Cost of visiting a node, relative to the cost of intersecting a primitive
+const TRAVERSAL_COST: f32 = 0.125;
+/// Maximum number of primitives a leaf is allowed to have, unless they can't be split apart
+const MAX_LEAF_SIZE: usize = 4;
+/// Depth past which nodes are made into leaves, however many primitives they have, so that
+/// degenerate distributions (i.e. clustered centroids) can't recurse without bounds
+const MAX_DEPTH: usize = 64;
+
+/// Bounding volume hierarchy over a set of primitives, built with the surface area heuristic.
+///
+/// The hierarchy only knows about the primitives' bounds, and refers to them by their index.
+#[derive(Debug, Clone, Default)]
+pub struct Bvh {
+    nodes: Vec<Node>,
+    indices: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    bounds: Aabb,
+    /// For leaves, the first primitive in `indices`. For interior nodes, the index of the
+    /// right child (the left child always follows its parent).
+    offset: u32,
+    /// Number of primitives in the leaf, or zero for interior nodes
+    count: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Primitive {
+    index: u32,
+    bounds: Aabb,
+    centroid: Vec3,
+}
+
+impl Bvh {
+    pub fn new(bounds: &[Aabb]) -> Self {
+        let mut primitives = bounds
+            .iter()
+            .enumerate()
+            .map(|(index, &bounds)| Primitive {
+                index: index as u32,
+                bounds,
+                centroid: bounds.centroid(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut this = Self {
+            nodes: Vec::with_capacity(2 * primitives.len()),
+            indices: Vec::with_capacity(primitives.len()),
+        };
+
+        if !primitives.is_empty() {
+            this.build(&mut primitives, 0);
+        }
+        return this;
+    }
+
+    /// Bounds of the whole hierarchy
+    #[inline]
+    pub fn bounds(&self) -> Aabb {
+        return self.nodes.first().map_or(Aabb::EMPTY, |node| node.bounds);
+    }
+
+    fn build(&mut self, primitives: &mut [Primitive], depth: usize) -> u32 {
+        let id = self.nodes.len();
+        let bounds = primitives.iter().fold(Aabb::EMPTY, |acc, x| acc.union(x.bounds));
+        self.nodes.push(Node {
+            bounds,
+            offset: 0,
+            count: 0,
+        });
+
+        let split = match depth < MAX_DEPTH {
+            true => Self::split(primitives, bounds),
+            false => None,
+        };
+        match split {
+            Some(mid) => {
+                let (left, right) = primitives.split_at_mut(mid);
+                self.build(left, depth + 1);
+                self.nodes[id].offset = self.build(right, depth + 1);
+            }
+            None => {
+                self.nodes[id].offset = self.indices.len() as u32;
+                self.nodes[id].count = primitives.len() as u32;
+                self.indices.extend(primitives.iter().map(|x| x.index));
+            }
+        }
+
+        return id as u32;
+    }
+
+    /// Partitions the primitives along the cheapest split, returning the size of the left half.
+    /// Returns `None` if the primitives are better off in a single leaf.
+    fn split(primitives: &mut [Primitive], bounds: Aabb) -> Option<usize> {
+        if primitives.len() <= 1 {
+            return None;
+        }
+
+        let centroids = Aabb::from_points(primitives.iter().map(|x| x.centroid));
+        let axis = centroids.longest_axis();
+        let min = centroids.min.as_array()[axis];
+        let extent = centroids.extent().as_array()[axis];
+
+        if !(extent > 0.0) || !extent.is_finite() {
+            return None;
+        }
+
+        let bin_of = |x: &Primitive| {
+            let offset = (x.centroid.as_array()[axis] - min) / extent;
+            usize::min((BINS as f32 * offset) as usize, BINS - 1)
+        };
+
+        let mut bins = [(Aabb::EMPTY, 0usize); BINS];
+        for x in primitives.iter() {
+            let bin = &mut bins[bin_of(x)];
+            bin.0 = bin.0.union(x.bounds);
+            bin.1 += 1;
+        }
+
+        // cost of splitting after each bin, sweeping from both sides
+        let mut costs = [0.0; BINS - 1];
+        let (mut acc_bounds, mut acc_count) = (Aabb::EMPTY, 0);
+        for i in 0..BINS - 1 {
+            acc_bounds = acc_bounds.union(bins[i].0);
+            acc_count += bins[i].1;
+            costs[i] = acc_count as f32 * acc_bounds.surface_area();
+        }
+
+        let (mut acc_bounds, mut acc_count) = (Aabb::EMPTY, 0);
+        for i in (1..BINS).rev() {
+            acc_bounds = acc_bounds.union(bins[i].0);
+            acc_count += bins[i].1;
+            costs[i - 1] += acc_count as f32 * acc_bounds.surface_area();
+        }
+
+        let (best, cost) = costs
+            .into_iter()
+            .enumerate()
+            .min_by(|(_, x), (_, y)| f32::total_cmp(x, y))?;
+
+        let leaf_cost = primitives.len() as f32;
+        let split_cost = TRAVERSAL_COST + cost / bounds.surface_area();
+        if primitives.len() <= MAX_LEAF_SIZE && leaf_cost <= split_cost {
+            return None;
+        }
+
+        let mid = partition(primitives, |x| bin_of(x) <= best);
+        if mid == 0 || mid == primitives.len() {
+            return None;
+        }
+        return Some(mid);
+    }
+
+    /// Visits the primitives that `ray` may hit within `[t_min, t_max]`, front to back.
+    ///
+    /// `hit` is called with the primitive's index and the current maximum distance, and returns
+    /// the intersection with the primitive (and its distance) if it's closer than that. The closest
+    /// intersection found is returned.
+    pub fn traverse<T>(
+        &self,
+        ray: Ray,
+        t_min: f32,
+        t_max: f32,
+        mut hit: impl FnMut(usize, f32) -> Option<(T, f32)>,
+    ) -> Option<T> {
+        let mut result = None;
+        let _ = self.walk(ray, t_min, t_max, |index, t_max| {
+            if let Some((value, t)) = hit(index, *t_max) {
+                result = Some(value);
+                *t_max = t;
+            }
+            return ControlFlow::Continue(());
+        });
+        return result;
+    }
+
+    /// Checks if `ray` hits any primitive within `[t_min, t_max]`, stopping as soon as `hit`
+    /// returns `true` for one of them.
+    pub fn any(&self, ray: Ray, t_min: f32, t_max: f32, mut hit: impl FnMut(usize) -> bool) -> bool {
+        return self
+            .walk(ray, t_min, t_max, |index, _| match hit(index) {
+                true => ControlFlow::Break(()),
+                false => ControlFlow::Continue(()),
+            })
+            .is_break();
+    }
+
+    fn walk(
+        &self,
+        ray: Ray,
+        t_min: f32,
+        mut t_max: f32,
+        mut visit: impl FnMut(usize, &mut f32) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
+        if self.nodes.is_empty() {
+            return ControlFlow::Continue(());
+        }
+
+        let inv_direction = Vec3::splat(1.0).wide_div(ray.direction.to_vec());
+        let mut stack = Vec::with_capacity(64);
+        stack.push(0u32);
+
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id as usize];
+            if node.bounds.intersect(ray.origin, inv_direction, t_min, t_max).is_none() {
+                continue;
+            }
+
+            if node.count > 0 {
+                let start = node.offset as usize;
+                for &index in &self.indices[start..start + node.count as usize] {
+                    visit(index as usize, &mut t_max)?;
+                }
+                continue;
+            }
+
+            // push the farthest child first, so that the closest one is visited next
+            let (left, right) = (id + 1, node.offset);
+            let enter = |id: u32| {
+                self.nodes[id as usize]
+                    .bounds
+                    .intersect(ray.origin, inv_direction, t_min, t_max)
+                    .unwrap_or(f32::INFINITY)
+            };
+
+            match enter(left) <= enter(right) {
+                true => stack.extend([right, left]),
+                false => stack.extend([left, right]),
+            }
+        }
+
+        return ControlFlow::Continue(());
+    }
+}
+
+/// Moves the elements matching `f` to the front of the slice, returning how many there are
+#[inline]
+fn partition<T>(slice: &mut [T], mut f: impl FnMut(&T) -> bool) -> usize {
+    let mut mid = 0;
+    for i in 0..slice.len() {
+        if f(&slice[i]) {
+            slice.swap(i, mid);
+            mid += 1;
+        }
+    }
+    return mid;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bvh, MAX_DEPTH};
+    use crate::{
+        math::{Aabb, Vec3},
+        object::{sphere::Sphere, Object, Ray},
+    };
+
+    #[test]
+    fn test_matches_linear_search() {
+        // deterministic pseudo-random values
+        let mut state = 0x2545f491u32;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state as f32) / (u32::MAX as f32)
+        };
+
+        let spheres = (0..1000)
+            .map(|_| {
+                let center = Vec3::new(20.0 * next() - 10.0, 20.0 * next() - 10.0, 20.0 * next() - 30.0);
+                Sphere::new(center, 0.5 * next())
+            })
+            .collect::<Vec<_>>();
+
+        let bounds = spheres
+            .iter()
+            .map(|x| Aabb::new(x.center - Vec3::splat(x.radius), x.center + Vec3::splat(x.radius)))
+            .collect::<Vec<_>>();
+        let bvh = Bvh::new(&bounds);
+
+        for _ in 0..500 {
+            let direction = Vec3::new(next() - 0.5, next() - 0.5, -1.0).unit();
+            let ray = Ray::new(Vec3::ZERO, direction);
+
+            let expected = spheres
+                .iter()
+                .filter_map(|x| x.hit(ray, 0.0, f32::INFINITY))
+                .map(|x| x.t)
+                .min_by(f32::total_cmp);
+
+            let found = bvh.traverse(ray, 0.0, f32::INFINITY, |i, t_max| {
+                spheres[i].hit(ray, 0.0, t_max).map(|x| (x.t, x.t))
+            });
+
+            assert_eq!(expected, found);
+        }
+    }
+
+    #[test]
+    fn test_degenerate_centroids() {
+        fn depth(bvh: &Bvh, id: usize) -> usize {
+            let node = bvh.nodes[id];
+            if node.count > 0 {
+                return 0;
+            }
+            return 1 + usize::max(depth(bvh, id + 1), depth(bvh, node.offset as usize));
+        }
+
+        // thousands of coincident primitives end up in a single leaf
+        let bounds = vec![Aabb::new(Vec3::splat(-1.0), Vec3::splat(1.0)); 5000];
+        let bvh = Bvh::new(&bounds);
+        assert_eq!((bvh.nodes.len(), bvh.indices.len()), (1, 5000));
+
+        // centroids that halve their distance every time peel off one by one, until the depth runs out
+        let bounds = (0..5000)
+            .map(|i| {
+                let center = Vec3::new(0.5f32.powi(i), 0.0, -1.0);
+                Aabb::new(center - Vec3::splat(1.0), center + Vec3::splat(1.0))
+            })
+            .collect::<Vec<_>>();
+        let bvh = Bvh::new(&bounds);
+        assert!(depth(&bvh, 0) <= MAX_DEPTH);
+
+        let mut hits = Vec::new();
+        let ray = Ray::new(Vec3::new(0.5, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0).unit());
+        bvh.traverse(ray, 0.0, f32::INFINITY, |i, _| {
+            hits.push(i);
+            return None::<((), f32)>;
+        });
+        hits.sort();
+        assert_eq!(hits, (0..5000).collect::<Vec<_>>());
+    }
+}
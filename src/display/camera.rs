@@ -0,0 +1,194 @@
+use super::Projection;
+use crate::{
+    math::{Mat4, Transform, UnitVec3, Vec2, Vec3, Versor},
+    object::Ray,
+};
+use std::f32::consts::{FRAC_PI_4, TAU};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    pub projection: Projection,
+    pub z_near: f32,
+    pub z_far: f32,
+    /// Position and orientation of the camera in world space.
+    /// The camera looks along its local -z axis, with +y pointing up.
+    pub pose: Transform,
+    /// Radius of the lens. A radius of zero makes a pinhole camera, with everything in focus.
+    /// Depth of field only applies to perspective and orthographic projections.
+    pub aperture_radius: f32,
+    /// Distance from the camera to the plane that's in perfect focus
+    pub focus_distance: f32,
+    /// Shape of the aperture, which out-of-focus highlights (bokeh) take
+    pub aperture: Aperture,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aperture {
+    Circle,
+    /// Regular polygon formed by `blades` diaphragm blades, rotated by `rotation` radians
+    Polygon { blades: u32, rotation: f32 },
+}
+
+impl Camera {
+    /// Creates a perspective camera, with a vertical field of view of `fov` radians
+    #[inline]
+    pub fn new(fov: f32, z_near: f32, z_far: f32) -> Self {
+        return Self::with_projection(Projection::Perspective { fov }, z_near, z_far);
+    }
+
+    #[inline]
+    pub fn with_projection(projection: Projection, z_near: f32, z_far: f32) -> Self {
+        debug_assert!(z_near < z_far);
+        return Self {
+            projection,
+            z_near,
+            z_far,
+            pose: Transform::default(),
+            aperture_radius: 0.0,
+            focus_distance: 1.0,
+            aperture: Aperture::Circle,
+        };
+    }
+
+    /// Creates a camera at `eye`, looking towards `target`, with `up` pointing
+    /// (as close as possible to) the top of the image.
+    #[inline]
+    pub fn look_at(eye: Vec3, target: Vec3, up: Vec3) -> Self {
+        let mut this = Self::default();
+        this.pose.position = eye;
+        this.pose.rotation = look_rotation((target - eye).unit(), up);
+        return this;
+    }
+
+    /// Projection matrix of the camera, if its projection is linear
+    #[inline]
+    pub fn transform(self, aspect_ratio: f32) -> Option<Mat4> {
+        return self.projection.matrix(aspect_ratio, self.z_near, self.z_far);
+    }
+
+    /// Returns the world-space ray going through `ndc`, a point on the image plane
+    /// ranging from `(-1, -1)` at the bottom left corner to `(1, 1)` at the top right one.
+    /// Returns `None` if the point is outside of the area covered by the projection.
+    ///
+    /// `lens` is a uniformly distributed point in `[0, 1)²`, used to pick the point of
+    /// the aperture the ray starts from.
+    #[inline]
+    pub fn ray(&self, ndc: Vec2, lens: Vec2, aspect_ratio: f32) -> Option<Ray> {
+        let (origin, direction) = self.projection.ray(ndc, aspect_ratio)?;
+
+        let (origin, direction) = match self.projection {
+            Projection::Perspective { .. } | Projection::Orthographic { .. } if self.aperture_radius > 0.0 => {
+                // every ray through `ndc` converges on the same point of the focal plane
+                let focus = origin + (self.focus_distance / -direction.z()) * direction;
+                let lens = self.aperture_radius * self.aperture.sample(lens);
+                let origin = origin + Vec3::new(lens.x(), lens.y(), 0.0);
+                (origin, focus - origin)
+            }
+            _ => (origin, direction),
+        };
+
+        return Some(Ray::new(
+            self.pose.position + self.pose.rotation.apply(origin),
+            self.pose.rotation.apply(direction).unit(),
+        ));
+    }
+}
+
+impl Aperture {
+    /// Maps a uniformly distributed point in `[0, 1)²` to a uniformly distributed point
+    /// inside the aperture, scaled to a radius of one.
+    pub fn sample(self, u: Vec2) -> Vec2 {
+        match self {
+            // https://pbr-book.org/3ed-2018/Monte_Carlo_Integration/2D_Sampling_with_Multidimensional_Transformations#ConcentricSampleDisk
+            Aperture::Circle => {
+                let offset = 2.0 * u - Vec2::splat(1.0);
+                let (x, y) = (offset.x(), offset.y());
+                if x == 0.0 && y == 0.0 {
+                    return Vec2::ZERO;
+                }
+
+                let (r, theta) = match x.abs() > y.abs() {
+                    true => (x, FRAC_PI_4 * (y / x)),
+                    false => (y, 2.0 * FRAC_PI_4 - FRAC_PI_4 * (x / y)),
+                };
+                return r * Vec2::new(theta.cos(), theta.sin());
+            }
+
+            Aperture::Polygon { blades, rotation } => {
+                // pick one of the triangles between the center and each side, and sample it uniformly
+                let blades = blades.max(3);
+                let scaled = u.x() * blades as f32;
+                let blade = u32::min(scaled as u32, blades - 1);
+                let u0 = scaled - blade as f32;
+
+                let vertex = |i: u32| {
+                    let angle = rotation + TAU * (i as f32) / (blades as f32);
+                    Vec2::new(angle.cos(), angle.sin())
+                };
+
+                let su = u0.sqrt();
+                return (su * (1.0 - u.y())) * vertex(blade) + (su * u.y()) * vertex(blade + 1);
+            }
+        }
+    }
+}
+
+/// Rotation that makes the camera's -z axis point towards `forward`
+#[inline]
+fn look_rotation(forward: UnitVec3, up: Vec3) -> Versor {
+    let right = match forward.cross_vec(up) {
+        x if x.sq_norm().is_normal() => x.unit(),
+        // `up` is parallel to `forward`, so any perpendicular direction will do
+        _ => forward.orthonormal_basis().0,
+    };
+    let up = right.cross(forward);
+    return Versor::from_basis(right, up, -forward);
+}
+
+impl Default for Camera {
+    #[inline]
+    fn default() -> Self {
+        Self::new(f32::to_radians(60.0), 0.01, 1000.0)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_look_at() {
+    let camera = Camera::look_at(Vec3::new(3.0, 0.0, 0.0), Vec3::new(3.0, 0.0, -5.0), Vec3::new(0.0, 1.0, 0.0));
+    let ray = camera.ray(Vec2::ZERO, Vec2::ZERO, 1.0).unwrap();
+    assert_eq!(ray.origin, Vec3::new(3.0, 0.0, 0.0));
+    assert!((ray.direction - Vec3::new(0.0, 0.0, -1.0)).norm() < 1e-5);
+
+    let camera = Camera::look_at(Vec3::new(0.0, 2.0, 0.0), Vec3::new(5.0, 2.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+    let ray = camera.ray(Vec2::ZERO, Vec2::ZERO, 1.0).unwrap();
+    assert!((ray.direction - Vec3::new(1.0, 0.0, 0.0)).norm() < 1e-5);
+
+    // the top of the image stays up, and the right side is to the right of the view direction
+    let ray = camera.ray(Vec2::new(1.0, 1.0), Vec2::ZERO, 1.0).unwrap();
+    assert!(ray.direction.y() > 0.0);
+    assert!(ray.direction.z() > 0.0);
+}
+
+#[cfg(test)]
+#[test]
+fn test_depth_of_field() {
+    let mut camera = Camera::look_at(Vec3::new(0.0, 1.0, 4.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+    camera.aperture_radius = 0.5;
+    camera.focus_distance = 4.0;
+
+    for aperture in [Aperture::Circle, Aperture::Polygon { blades: 6, rotation: 0.3 }] {
+        camera.aperture = aperture;
+
+        // rays through the same point of the image start all over the lens, but meet on the focal plane
+        let ndc = Vec2::new(0.2, -0.4);
+        let focus = camera.ray(ndc, Vec2::splat(0.5), 1.0).unwrap();
+        let focus = focus.position_at(4.0 / -focus.direction.z());
+
+        for lens in [Vec2::new(0.1, 0.9), Vec2::new(0.7, 0.2), Vec2::new(0.99, 0.5)] {
+            let ray = camera.ray(ndc, lens, 1.0).unwrap();
+            assert!(ray.origin.distance(Vec3::new(0.0, 1.0, 4.0)) <= 0.5 + 1e-5);
+            assert!(ray.position_at(4.0 / -ray.direction.z()).distance(focus) < 1e-4);
+        }
+    }
+}
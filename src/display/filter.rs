@@ -0,0 +1,139 @@
+use crate::math::Vec2;
+use std::f32::consts::PI;
+
+/// Reconstruction filter, weighting the samples that land around each pixel's center.
+///
+/// Filters are separable, and zero outside of `[-radius, radius)` along each axis.
+/// Their weights are normalized when accumulated, so they don't need to integrate to one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    /// Weighs every sample within the radius equally. A radius of `0.5` only takes the pixel's own samples.
+    Box { radius: f32 },
+    /// Weight falling off linearly with the distance to the pixel's center
+    Tent { radius: f32 },
+    /// Gaussian with standard deviation `sigma`, shifted down so that it reaches zero at the radius
+    Gaussian { radius: f32, sigma: f32 },
+    /// Mitchell-Netravali cubic, with the `b = c = 1/3` recommended by its authors striking a balance
+    /// between blurring and ringing
+    Mitchell { radius: f32, b: f32, c: f32 },
+    /// Sinc, windowed by the central lobe of a sinc stretched over the radius
+    Lanczos { radius: f32 },
+}
+
+impl Filter {
+    /// Mitchell-Netravali filter with its recommended parameters
+    #[inline]
+    pub const fn mitchell(radius: f32) -> Self {
+        return Self::Mitchell {
+            radius,
+            b: 1.0 / 3.0,
+            c: 1.0 / 3.0,
+        };
+    }
+
+    #[inline]
+    pub fn radius(self) -> f32 {
+        return match self {
+            Self::Box { radius }
+            | Self::Tent { radius }
+            | Self::Gaussian { radius, .. }
+            | Self::Mitchell { radius, .. }
+            | Self::Lanczos { radius } => radius,
+        };
+    }
+
+    /// Weight of a sample at `offset` from the pixel's center, in pixels
+    #[inline]
+    pub fn eval(self, offset: Vec2) -> f32 {
+        return self.eval_1d(offset.x()) * self.eval_1d(offset.y());
+    }
+
+    fn eval_1d(self, x: f32) -> f32 {
+        let radius = self.radius();
+        if !(-radius <= x && x < radius) {
+            return 0.0;
+        }
+
+        return match self {
+            Self::Box { .. } => 1.0,
+            Self::Tent { radius } => 1.0 - x.abs() / radius,
+            Self::Gaussian { radius, sigma } => {
+                let gaussian = |x: f32| f32::exp(-(x * x) / (2.0 * sigma * sigma));
+                f32::max(gaussian(x) - gaussian(radius), 0.0)
+            }
+            Self::Mitchell { radius, b, c } => mitchell(2.0 * x / radius, b, c),
+            Self::Lanczos { radius } => sinc(x) * sinc(x / radius),
+        };
+    }
+}
+
+impl Default for Filter {
+    #[inline]
+    fn default() -> Self {
+        return Self::Box { radius: 0.5 };
+    }
+}
+
+/// Mitchell-Netravali cubic over `[-2, 2]`
+// "Reconstruction Filters in Computer Graphics", Don P. Mitchell and Arun N. Netravali
+#[inline]
+fn mitchell(x: f32, b: f32, c: f32) -> f32 {
+    let x = x.abs();
+    let result = match x < 1.0 {
+        true => (12.0 - 9.0 * b - 6.0 * c) * x.powi(3) + (-18.0 + 12.0 * b + 6.0 * c) * x.powi(2) + (6.0 - 2.0 * b),
+        false => {
+            (-b - 6.0 * c) * x.powi(3) + (6.0 * b + 30.0 * c) * x.powi(2) + (-12.0 * b - 48.0 * c) * x
+                + (8.0 * b + 24.0 * c)
+        }
+    };
+    return result / 6.0;
+}
+
+/// Normalized sinc
+#[inline]
+fn sinc(x: f32) -> f32 {
+    let x = PI * x;
+    return match x.abs() < 1e-5 {
+        true => 1.0,
+        false => x.sin() / x,
+    };
+}
+
+#[cfg(test)]
+#[test]
+fn test_filters() {
+    for filter in [
+        Filter::Box { radius: 0.5 },
+        Filter::Tent { radius: 1.0 },
+        Filter::Gaussian { radius: 1.5, sigma: 0.5 },
+        Filter::mitchell(2.0),
+        Filter::Lanczos { radius: 3.0 },
+    ] {
+        let radius = filter.radius();
+        assert!(filter.eval(Vec2::ZERO) > 0.0, "{filter:?}");
+        assert_eq!(filter.eval(Vec2::new(radius, 0.0)), 0.0, "{filter:?}");
+        assert_eq!(filter.eval(Vec2::new(0.0, -radius - 0.1)), 0.0, "{filter:?}");
+
+        // symmetric, and (mostly) decreasing away from the center
+        let x = 0.3 * radius;
+        assert!((filter.eval(Vec2::new(x, 0.0)) - filter.eval(Vec2::new(-x, 0.0))).abs() < 1e-6);
+        assert!(filter.eval(Vec2::new(x, 0.0)) <= filter.eval(Vec2::ZERO), "{filter:?}");
+    }
+
+    // the box filter only sees samples within the pixel, exactly once each
+    let filter = Filter::Box { radius: 0.5 };
+    assert_eq!(filter.eval(Vec2::new(-0.5, -0.5)), 1.0);
+    assert_eq!(filter.eval(Vec2::new(0.5, 0.0)), 0.0);
+
+    // Mitchell-Netravali is continuous at its knots, and the (1/3, 1/3) cubic has a negative lobe
+    assert!((mitchell(1.0 - 1e-4, 1.0 / 3.0, 1.0 / 3.0) - mitchell(1.0 + 1e-4, 1.0 / 3.0, 1.0 / 3.0)).abs() < 1e-3);
+    assert!(mitchell(1.5, 1.0 / 3.0, 1.0 / 3.0) < 0.0);
+    assert!(mitchell(2.0, 1.0 / 3.0, 1.0 / 3.0).abs() < 1e-6);
+
+    // the Lanczos window closes at the radius, whether or not it falls on one of the sinc's zeros
+    for radius in [2.0, 2.5, 3.0] {
+        let filter = Filter::Lanczos { radius };
+        assert!(filter.eval(Vec2::new(radius - 1e-3, 0.0)).abs() < 1e-3, "{radius}");
+        assert!(filter.eval(Vec2::new(0.0, 1e-3 - radius)).abs() < 1e-3, "{radius}");
+    }
+}
@@ -0,0 +1,294 @@
+flat_mod! { camera, filter, projection, tonemap }
+
+use crate::{
+    format::{self, OutputError, OutputFormat},
+    math::{Vec2, Vec3, Vec4},
+    object::Ray,
+    sampler::{Independent, SampleStream, Sampler},
+};
+use image::{ImageBuffer, RgbImage, Rgba, RgbaImage};
+use rayon::{
+    prelude::{IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator},
+    slice::ParallelSliceMut,
+};
+use std::path::Path;
+
+/// Rows traced together by [`Framebuffer::update`], which splats them into a buffer of their own
+const BAND_HEIGHT: usize = 16;
+
+/// Linear, high dynamic range RGBA image, with 32-bit float channels
+pub type HdrImage = ImageBuffer<Rgba<f32>, Box<[f32]>>;
+
+#[derive(Debug)]
+pub struct Framebuffer {
+    /// Linear radiance of every pixel. Alpha is the fraction of the pixel covered by the camera's projection.
+    pixels: HdrImage,
+    /// Weighted sum of the samples around every pixel, and the sum of their weights
+    weights: Box<[(Vec4, f32)]>,
+    aspect_ratio: f32,
+    pub camera: Camera,
+    /// Picks the rays shot through every pixel
+    pub sampler: Box<dyn Sampler>,
+    /// Blends the samples around every pixel into its final color
+    pub filter: Filter,
+    /// Maps the linear radiance to the values of 8-bit images
+    pub display_transform: DisplayTransform,
+}
+
+impl Framebuffer {
+    #[inline]
+    pub fn new(width: u32, height: u32, camera: Camera) -> anyhow::Result<Self> {
+        let aspect_ratio = (width as f32) / (height as f32);
+
+        return Ok(Self {
+            pixels: unsafe {
+                ImageBuffer::from_raw(
+                    width,
+                    height,
+                    vec![0.0; 4 * (width as usize) * (height as usize)].into_boxed_slice(),
+                )
+                .unwrap_unchecked()
+            },
+            weights: vec![(Vec4::ZERO, 0.0); (width as usize) * (height as usize)].into_boxed_slice(),
+            camera,
+            aspect_ratio,
+            sampler: Box::new(Independent::new(1, 0)),
+            filter: Filter::default(),
+            display_transform: DisplayTransform::default(),
+        });
+    }
+
+    #[inline]
+    pub fn with_sampler(self, sampler: impl 'static + Sampler) -> Self {
+        return Self {
+            sampler: Box::new(sampler),
+            ..self
+        };
+    }
+
+    #[inline]
+    pub fn with_filter(self, filter: Filter) -> Self {
+        return Self { filter, ..self };
+    }
+
+    #[inline]
+    pub fn with_display_transform(self, display_transform: DisplayTransform) -> Self {
+        return Self {
+            display_transform,
+            ..self
+        };
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        return self.pixels.width();
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        return self.pixels.height();
+    }
+
+    /// Shoots the sampler's rays through every pixel, and shades them with `f`, which
+    /// returns the linear radiance carried back by the ray. `f` may draw further values
+    /// of the sample from the stream it's given.
+    ///
+    /// Samples are accumulated with the ones of previous updates, until the framebuffer is cleared.
+    #[inline]
+    pub fn update<T, I: FnOnce(&Camera) -> T, F: Fn(Ray, &mut SampleStream, &T) -> Vec3>(
+        &mut self,
+        init: I,
+        f: F,
+    ) where
+        T: Sync,
+        F: Send + Sync,
+    {
+        let t = &init(&self.camera);
+
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        let size = Vec2::new(width as f32, height as f32);
+        let camera = &self.camera;
+        let sampler: &dyn Sampler = &*self.sampler;
+        let samples = sampler.samples_per_pixel().max(1) as usize;
+        let aspect_ratio = self.aspect_ratio;
+
+        // trace the samples in bands of rows, splatting each of them into the pixels within the filter's reach.
+        // Every band has its own buffer, which spills over `reach` rows on both sides, so that bands can be
+        // traced in parallel without keeping every sample of the frame around.
+        let filter = self.filter;
+        let reach = filter.radius().ceil() as usize;
+        let band_height = BAND_HEIGHT.max(reach);
+        let bands = (0..height.div_ceil(band_height))
+            .into_par_iter()
+            .map(|band| {
+                let (start, end) = (band * band_height, ((band + 1) * band_height).min(height));
+                let (top, bottom) = (start.saturating_sub(reach), (end + reach).min(height));
+                let mut buffer = vec![(Vec4::ZERO, 0.0); width * (bottom - top)];
+
+                for i in start..end {
+                    for j in 0..width {
+                        let pixel = [j as u32, i as u32];
+                        for index in 0..samples as u32 {
+                            let offset = sampler.sample_2d(pixel, index, 0);
+
+                            // image rows go downwards, while the image plane's y axis goes upwards
+                            let position = Vec2::new(j as f32 + offset.x(), i as f32 + offset.y());
+                            let ndc = 2. * position.wide_div(size) - Vec2::splat(1.0);
+                            let ndc = Vec2::new(ndc.x(), -ndc.y());
+
+                            // samples outside of the projection still count towards the pixel's coverage
+                            let color = match camera.ray(ndc, sampler.sample_2d(pixel, index, 1), aspect_ratio) {
+                                Some(ray) => {
+                                    let mut stream = SampleStream::new(sampler, pixel, index);
+                                    Vec4::from_vec3(f(ray, &mut stream, t), 1.0)
+                                }
+                                None => Vec4::ZERO,
+                            };
+
+                            for y in i.saturating_sub(reach)..(i + reach + 1).min(height) {
+                                for x in j.saturating_sub(reach)..(j + reach + 1).min(width) {
+                                    let center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                                    let w = filter.eval(position - center);
+                                    if w != 0.0 {
+                                        let (sum, weight) = &mut buffer[(y - top) * width + x];
+                                        *sum += w * color;
+                                        *weight += w;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                (top, buffer)
+            })
+            .collect::<Vec<_>>();
+
+        // add up the bands overlapping every row
+        self.weights.par_chunks_exact_mut(width).enumerate().for_each(|(y, row)| {
+            for (top, buffer) in bands.iter() {
+                let rows = buffer.len() / width;
+                if (*top..top + rows).contains(&y) {
+                    let band_row = &buffer[(y - top) * width..(y - top + 1) * width];
+                    for ((sum, weight), &(band_sum, band_weight)) in row.iter_mut().zip(band_row) {
+                        *sum += band_sum;
+                        *weight += band_weight;
+                    }
+                }
+            }
+        });
+
+        let weights = &self.weights;
+        self.pixels
+            .par_chunks_exact_mut(4)
+            .zip(weights.par_iter())
+            .for_each(|(pixel, &(sum, weight))| {
+                // filters with negative lobes may leave pixels with no weight at all
+                let color = match weight > 0.0 {
+                    true => sum / weight,
+                    false => Vec4::ZERO,
+                };
+                pixel.copy_from_slice(color.as_array());
+            });
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.pixels.fill(0.0);
+        self.weights.fill((Vec4::ZERO, 0.0));
+    }
+
+    /// Linear radiance of every pixel, as accumulated so far
+    #[inline]
+    pub fn pixels(&self) -> &HdrImage {
+        return &self.pixels;
+    }
+
+    /// Converts the image to 8 bits per channel, through the framebuffer's display transform
+    #[inline]
+    pub fn to_rgb8(&self) -> RgbImage {
+        return self.display_transform.to_rgb8(&self.pixels);
+    }
+
+    /// Converts the image to 8 bits per channel, through the framebuffer's display transform.
+    /// Alpha is linear, and isn't premultiplied.
+    #[inline]
+    pub fn to_rgba8(&self) -> RgbaImage {
+        return self.display_transform.to_rgba8(&self.pixels);
+    }
+
+    /// Saves the image, in the format given by the path's extension.
+    /// Formats with a low dynamic range go through the framebuffer's display transform.
+    #[inline]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), OutputError> {
+        return format::save(path, &self.pixels, self.display_transform);
+    }
+
+    /// Saves the image in the given format.
+    /// Formats with a low dynamic range go through the framebuffer's display transform.
+    #[inline]
+    pub fn save_with_format(&self, path: impl AsRef<Path>, output: OutputFormat) -> Result<(), OutputError> {
+        return format::save_with_format(path, &self.pixels, self.display_transform, output);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_hdr_framebuffer() {
+    let mut frame = Framebuffer::new(4, 2, Camera::default())
+        .unwrap()
+        .with_sampler(crate::sampler::Stratified::new(4, 0))
+        .with_filter(Filter::mitchell(2.0));
+    frame.update(|_| (), |_, _, _| Vec3::new(4.0, 0.5, -1.0));
+
+    // highlights are kept, and every pixel gets a normalized weight
+    for pixel in frame.pixels().pixels() {
+        let [r, g, b, a] = pixel.0;
+        assert!((r - 4.0).abs() < 1e-4 && (g - 0.5).abs() < 1e-4 && (b + 1.0).abs() < 1e-4);
+        assert!((a - 1.0).abs() < 1e-4);
+    }
+
+    frame.display_transform = DisplayTransform::new(0.0, ToneMap::Clamp, TransferFunction::Linear);
+    assert!(frame.to_rgb8().pixels().all(|x| x.0 == [255, 128, 0]));
+    frame.display_transform = DisplayTransform::new(-3.0, ToneMap::Clamp, TransferFunction::Srgb);
+    assert!(frame.to_rgb8().pixels().all(|x| x.0 == [188, 71, 0]));
+}
+
+#[cfg(test)]
+#[test]
+fn test_splat_matches_gather() {
+    let sampler = crate::sampler::Sobol::new(4, 3);
+    let filter = Filter::mitchell(2.0);
+    let (width, height) = (7, 37);
+    let mut frame = Framebuffer::new(width, height, Camera::default())
+        .unwrap()
+        .with_sampler(sampler)
+        .with_filter(filter);
+
+    // a color that only depends on the sample, so that it can be computed again below
+    let color = |u: Vec2| Vec3::new(u.x(), u.y(), 1.0);
+    frame.update(|_| (), |_, stream, _| color(stream.next_2d()));
+
+    // gather every sample within the filter's reach of every pixel
+    let reach = 2;
+    for (x, y, pixel) in frame.pixels().enumerate_pixels() {
+        let (mut sum, mut weight) = (Vec4::ZERO, 0.0);
+        for j in x.saturating_sub(reach)..(x + reach + 1).min(width) {
+            for i in y.saturating_sub(reach)..(y + reach + 1).min(height) {
+                for index in 0..sampler.samples_per_pixel() {
+                    let offset = sampler.sample_2d([j, i], index, 0);
+                    let u = sampler.sample_2d([j, i], index, SampleStream::CAMERA_DIMENSIONS);
+                    let position = Vec2::new(j as f32 + offset.x(), i as f32 + offset.y());
+                    let w = filter.eval(position - Vec2::new(x as f32 + 0.5, y as f32 + 0.5));
+                    sum += w * Vec4::from_vec3(color(u), 1.0);
+                    weight += w;
+                }
+            }
+        }
+
+        let expected = *(sum / weight).as_array();
+        for (a, b) in pixel.0.into_iter().zip(expected) {
+            assert!((a - b).abs() < 1e-4, "({x}, {y}): {:?} {expected:?}", pixel.0);
+        }
+    }
+}
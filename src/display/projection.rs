@@ -0,0 +1,134 @@
+use crate::math::{Mat4, Vec2, Vec3};
+use std::f32::consts::{FRAC_PI_2, PI};
+
+/// Maps points of the image to the rays leaving the camera
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    /// Pinhole projection, with a vertical field of view of `fov` radians
+    Perspective { fov: f32 },
+    /// Parallel projection, covering `height` world units vertically
+    Orthographic { height: f32 },
+    /// Equidistant fisheye, covering `fov` radians across the circle inscribed in the image.
+    /// Points outside of that circle don't map to any ray.
+    Fisheye { fov: f32 },
+    /// Full 360° panorama, mapping longitude to the horizontal axis and latitude to the vertical one.
+    /// Images are expected to have an aspect ratio of 2:1.
+    Equirectangular,
+}
+
+impl Projection {
+    /// Projection matrix mapping camera space onto clip space, if the projection is linear
+    #[inline]
+    pub fn matrix(self, aspect_ratio: f32, z_near: f32, z_far: f32) -> Option<Mat4> {
+        let zm = z_far - z_near;
+        let zp = z_far + z_near;
+
+        return match self {
+            Projection::Perspective { fov } => {
+                let yy = f32::tan(fov / 2.0).recip();
+                Some(Mat4::from_array([
+                    [yy / aspect_ratio, 0.0, 0.0, 0.0],
+                    [0.0, yy, 0.0, 0.0],
+                    [0.0, 0.0, -zp / zm, (-2.0 * z_far * z_near) / zm],
+                    [0.0, 0.0, -1.0, 0.0],
+                ]))
+            }
+            Projection::Orthographic { height } => {
+                let yy = 2.0 / height;
+                Some(Mat4::from_array([
+                    [yy / aspect_ratio, 0.0, 0.0, 0.0],
+                    [0.0, yy, 0.0, 0.0],
+                    [0.0, 0.0, -2.0 / zm, -zp / zm],
+                    [0.0, 0.0, 0.0, 1.0],
+                ]))
+            }
+            Projection::Fisheye { .. } | Projection::Equirectangular => None,
+        };
+    }
+
+    /// Returns the camera-space origin and direction of the ray going through `ndc`, a point
+    /// of the image ranging from `(-1, -1)` at the bottom left corner to `(1, 1)` at the top right one.
+    ///
+    /// The direction isn't necessarily normalized.
+    pub fn ray(self, ndc: Vec2, aspect_ratio: f32) -> Option<(Vec3, Vec3)> {
+        let (x, y) = (ndc.x(), ndc.y());
+
+        return match self {
+            Projection::Perspective { fov } => {
+                let scale = f32::tan(fov / 2.0);
+                Some((Vec3::ZERO, Vec3::new(x * scale * aspect_ratio, y * scale, -1.0)))
+            }
+
+            Projection::Orthographic { height } => {
+                let scale = height / 2.0;
+                Some((Vec3::new(x * scale * aspect_ratio, y * scale, 0.0), Vec3::new(0.0, 0.0, -1.0)))
+            }
+
+            // https://en.wikipedia.org/wiki/Fisheye_lens#Mapping_function
+            Projection::Fisheye { fov } => {
+                // keep the image circle round on non-square images
+                let (x, y) = match aspect_ratio >= 1.0 {
+                    true => (x * aspect_ratio, y),
+                    false => (x, y / aspect_ratio),
+                };
+
+                let r = f32::hypot(x, y);
+                if r > 1.0 {
+                    return None;
+                }
+
+                let theta = r * fov / 2.0;
+                let phi = f32::atan2(y, x);
+                Some((
+                    Vec3::ZERO,
+                    Vec3::new(theta.sin() * phi.cos(), theta.sin() * phi.sin(), -theta.cos()),
+                ))
+            }
+
+            Projection::Equirectangular => {
+                let longitude = x * PI;
+                let latitude = y * FRAC_PI_2;
+                Some((
+                    Vec3::ZERO,
+                    Vec3::new(
+                        latitude.cos() * longitude.sin(),
+                        latitude.sin(),
+                        -latitude.cos() * longitude.cos(),
+                    ),
+                ))
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_projections() {
+    let close = |a: Vec3, b: Vec3| (a.unit() - b.unit()).norm() < 1e-5;
+
+    for projection in [
+        Projection::Perspective { fov: 1.0 },
+        Projection::Orthographic { height: 2.0 },
+        Projection::Fisheye { fov: PI },
+        Projection::Equirectangular,
+    ] {
+        let (_, direction) = projection.ray(Vec2::ZERO, 1.0).unwrap();
+        assert!(close(direction, Vec3::new(0.0, 0.0, -1.0)), "{projection:?}");
+    }
+
+    let (origin, direction) = Projection::Orthographic { height: 2.0 }.ray(Vec2::new(1.0, -1.0), 2.0).unwrap();
+    assert_eq!(origin, Vec3::new(2.0, -1.0, 0.0));
+    assert_eq!(direction, Vec3::new(0.0, 0.0, -1.0));
+
+    // the edge of a 180° fisheye looks sideways, and its corners are outside the image circle
+    let (_, direction) = Projection::Fisheye { fov: PI }.ray(Vec2::new(1.0, 0.0), 1.0).unwrap();
+    assert!(close(direction, Vec3::new(1.0, 0.0, 0.0)));
+    assert!(Projection::Fisheye { fov: PI }.ray(Vec2::new(1.0, 1.0), 1.0).is_none());
+
+    let (_, direction) = Projection::Equirectangular.ray(Vec2::new(1.0, 0.0), 2.0).unwrap();
+    assert!(close(direction, Vec3::new(0.0, 0.0, 1.0)));
+    let (_, direction) = Projection::Equirectangular.ray(Vec2::new(0.5, 0.0), 2.0).unwrap();
+    assert!(close(direction, Vec3::new(1.0, 0.0, 0.0)));
+    let (_, direction) = Projection::Equirectangular.ray(Vec2::new(0.0, 1.0), 2.0).unwrap();
+    assert!(close(direction, Vec3::new(0.0, 1.0, 0.0)));
+}
@@ -0,0 +1,217 @@
+use super::HdrImage;
+use crate::math::Vec3;
+use image::{ImageBuffer, Rgb, RgbImage, Rgba, RgbaImage};
+
+/// Compresses linear radiance into the `[0, 1]` range of display values.
+///
+/// Operators work on every channel independently.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ToneMap {
+    /// Clips anything over one
+    #[default]
+    Clamp,
+    /// `x / (1 + x)`, which never quite reaches white
+    Reinhard,
+    /// Reinhard's operator, scaled so that `white` (and anything brighter) maps to one
+    ReinhardExtended { white: f32 },
+    /// John Hable's filmic curve from Uncharted 2, with a white point of 11.2
+    Hable,
+    /// Stephen Hill's fit of the ACES reference rendering and sRGB output transforms
+    Aces,
+}
+
+/// Encodes linear display values for storage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransferFunction {
+    /// Stores the values as they are
+    Linear,
+    /// sRGB's opto-electronic transfer function, which is what 8-bit images are expected to use
+    #[default]
+    Srgb,
+}
+
+/// Converts linear radiance into display-ready values, in `[0, 1]`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DisplayTransform {
+    /// Exposure compensation, in stops. Every stop doubles the brightness of the image.
+    pub exposure: f32,
+    pub tone_map: ToneMap,
+    pub transfer: TransferFunction,
+}
+
+impl DisplayTransform {
+    #[inline]
+    pub const fn new(exposure: f32, tone_map: ToneMap, transfer: TransferFunction) -> Self {
+        return Self {
+            exposure,
+            tone_map,
+            transfer,
+        };
+    }
+
+    #[inline]
+    pub fn apply(self, color: Vec3) -> Vec3 {
+        let color = self.tone_map.apply(f32::exp2(self.exposure) * color);
+        return map(color, |x| self.transfer.encode(x));
+    }
+
+    /// Converts the image to 8 bits per channel
+    pub fn to_rgb8(self, image: &HdrImage) -> RgbImage {
+        return RgbImage::from_fn(image.width(), image.height(), |x, y| {
+            let [r, g, b, _] = image.get_pixel(x, y).0;
+            Rgb(self.apply(Vec3::new(r, g, b)).to_array().map(|x| quantize(x, 255.0) as u8))
+        });
+    }
+
+    /// Converts the image to 8 bits per channel. Alpha is linear, and isn't premultiplied.
+    pub fn to_rgba8(self, image: &HdrImage) -> RgbaImage {
+        return RgbaImage::from_fn(image.width(), image.height(), |x, y| {
+            let [r, g, b, a] = image.get_pixel(x, y).0;
+            let [r, g, b] = self.apply(Vec3::new(r, g, b)).to_array();
+            Rgba([r, g, b, a].map(|x| quantize(x, 255.0) as u8))
+        });
+    }
+
+    /// Converts the image to 16 bits per channel
+    pub fn to_rgb16(self, image: &HdrImage) -> ImageBuffer<Rgb<u16>, Vec<u16>> {
+        return ImageBuffer::from_fn(image.width(), image.height(), |x, y| {
+            let [r, g, b, _] = image.get_pixel(x, y).0;
+            Rgb(self.apply(Vec3::new(r, g, b)).to_array().map(|x| quantize(x, 65535.0) as u16))
+        });
+    }
+}
+
+/// Maps a value in `[0, 1]` to the closest integer in `[0, max]`, clamping it to the range first
+#[inline]
+fn quantize(x: f32, max: f32) -> f32 {
+    // NaNs end up as zero once cast
+    return (max * x).round().clamp(0.0, max);
+}
+
+impl ToneMap {
+    #[inline]
+    pub fn apply(self, color: Vec3) -> Vec3 {
+        // negative radiance isn't meaningful, and NaNs end up as black too
+        let color = map(color, |x| f32::max(x, 0.0));
+
+        let color = match self {
+            Self::Clamp => color,
+            Self::Reinhard => map(color, |x| x / (1.0 + x)),
+            Self::ReinhardExtended { white } => map(color, |x| x * (1.0 + x / (white * white)) / (1.0 + x)),
+            Self::Hable => map(color, |x| hable(x) / hable(HABLE_WHITE)),
+            Self::Aces => {
+                let color = map(mul(ACES_INPUT, color), |x| {
+                    (x * (x + 0.0245786) - 0.000090537) / (x * (0.983729 * x + 0.4329510) + 0.238081)
+                });
+                mul(ACES_OUTPUT, color)
+            }
+        };
+
+        return map(color, |x| x.clamp(0.0, 1.0));
+    }
+}
+
+impl TransferFunction {
+    #[inline]
+    pub fn encode(self, x: f32) -> f32 {
+        return match self {
+            Self::Linear => x,
+            Self::Srgb if x <= 0.0031308 => 12.92 * x,
+            Self::Srgb => 1.055 * x.powf(1.0 / 2.4) - 0.055,
+        };
+    }
+
+    #[inline]
+    pub fn decode(self, x: f32) -> f32 {
+        return match self {
+            Self::Linear => x,
+            Self::Srgb if x <= 0.04045 => x / 12.92,
+            Self::Srgb => ((x + 0.055) / 1.055).powf(2.4),
+        };
+    }
+}
+
+const HABLE_WHITE: f32 = 11.2;
+
+#[inline]
+fn hable(x: f32) -> f32 {
+    const A: f32 = 0.15; // shoulder strength
+    const B: f32 = 0.50; // linear strength
+    const C: f32 = 0.10; // linear angle
+    const D: f32 = 0.20; // toe strength
+    const E: f32 = 0.02; // toe numerator
+    const F: f32 = 0.30; // toe denominator
+    return ((x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F)) - E / F;
+}
+
+/// sRGB to the ACES RRT's input space, with the 6500K to ACES white point adaptation
+const ACES_INPUT: [Vec3; 3] = [
+    Vec3::new(0.59719, 0.35458, 0.04823),
+    Vec3::new(0.07600, 0.90834, 0.01566),
+    Vec3::new(0.02840, 0.13383, 0.83777),
+];
+
+/// ODT output space back to linear sRGB
+const ACES_OUTPUT: [Vec3; 3] = [
+    Vec3::new(1.60475, -0.53108, -0.07367),
+    Vec3::new(-0.10208, 1.10813, -0.00605),
+    Vec3::new(-0.00327, -0.07276, 1.07602),
+];
+
+#[inline]
+fn map(v: Vec3, f: impl FnMut(f32) -> f32) -> Vec3 {
+    return Vec3::from_array(v.to_array().map(f));
+}
+
+/// Multiplies a 3x3 matrix, given by its rows, with a vector
+#[inline]
+fn mul([x, y, z]: [Vec3; 3], v: Vec3) -> Vec3 {
+    return Vec3::new(x * v, y * v, z * v);
+}
+
+#[cfg(test)]
+#[test]
+fn test_tone_mapping() {
+    let operators = [
+        ToneMap::Clamp,
+        ToneMap::Reinhard,
+        ToneMap::ReinhardExtended { white: 4.0 },
+        ToneMap::Hable,
+        ToneMap::Aces,
+    ];
+
+    for op in operators {
+        // monotonic, and within range
+        let mut prev = -1.0;
+        for i in 0..100 {
+            let x = op.apply(Vec3::splat(0.2 * i as f32)).x();
+            assert!((0.0..=1.0).contains(&x) && x >= prev, "{op:?}");
+            prev = x;
+        }
+        assert!(op.apply(Vec3::ZERO).x() < 1e-3, "{op:?}");
+        assert_eq!(op.apply(Vec3::splat(f32::NAN)), op.apply(Vec3::ZERO), "{op:?}");
+    }
+
+    assert!((ToneMap::Reinhard.apply(Vec3::splat(1.0)).x() - 0.5).abs() < 1e-6);
+    assert!((ToneMap::ReinhardExtended { white: 4.0 }.apply(Vec3::splat(4.0)).x() - 1.0).abs() < 1e-6);
+    assert!((ToneMap::Hable.apply(Vec3::splat(HABLE_WHITE)).x() - 1.0).abs() < 1e-6);
+
+    // exposure is applied before the operator
+    let transform = DisplayTransform::new(1.0, ToneMap::Reinhard, TransferFunction::Linear);
+    assert!((transform.apply(Vec3::splat(0.5)).x() - 0.5).abs() < 1e-6);
+}
+
+#[cfg(test)]
+#[test]
+fn test_srgb() {
+    let srgb = TransferFunction::Srgb;
+    assert_eq!(srgb.encode(0.0), 0.0);
+    assert!((srgb.encode(1.0) - 1.0).abs() < 1e-6);
+    assert!((srgb.encode(0.5) - 0.735357).abs() < 1e-5);
+    assert!((srgb.encode(0.002) - 0.02584).abs() < 1e-5);
+
+    for i in 0..=10 {
+        let x = i as f32 / 10.0;
+        assert!((srgb.decode(srgb.encode(x)) - x).abs() < 1e-5);
+    }
+}
@@ -1,26 +1,13 @@
-use crate::{object::{Object, Ray}, math::Vec3};
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-#[non_exhaustive]
-pub struct ReflectInfo {
-    pub color: Vec3, // rgb
-    pub ray: Ray
-}
+use crate::{material::DynMaterial, object::Object};
 
 pub struct Element<T> {
     pub object: T,
-    pub material: Material,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Material {
-    pub color: Vec3,
-    pub reflectiveness: Vec3
+    pub material: DynMaterial,
 }
 
 impl<T: Object> Element<T> {
     #[inline]
-    pub fn new (object: T, material: Material) -> Self {
+    pub fn new (object: T, material: DynMaterial) -> Self {
         return Self { object, material }
     }
 
@@ -35,14 +22,7 @@ impl<T: Object> Element<T> {
 
 impl<'a> Element<Box<dyn 'a + Object>> {
     #[inline]
-    pub fn new_unzise (object: impl 'a + Object, material: Material) -> Self {
+    pub fn new_unzise (object: impl 'a + Object, material: DynMaterial) -> Self {
         return Element::new(object, material).into_dyn()
     }
 }
-
-impl Material {
-    #[inline]
-    pub const fn new (color: Vec3, reflectiveness: Vec3) -> Self {
-        Self { color, reflectiveness }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,164 @@
+use crate::display::HdrImage;
+use exr::prelude::{
+    f16, AnyChannel, AnyChannels, Encoding, FlatSamples, Image, ImageAttributes, IntegerBounds, Layer,
+    LayerAttributes, WritableImage,
+};
+use std::{
+    io::{Seek, Write},
+    path::Path,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExrError {
+    #[error(transparent)]
+    Exr(#[from] exr::error::Error),
+    #[error("no layers to write")]
+    Empty,
+    #[error("layer '{name}' is {width}x{height}, expected {expected_width}x{expected_height}")]
+    Size {
+        name: String,
+        width: u32,
+        height: u32,
+        expected_width: u32,
+        expected_height: u32,
+    },
+}
+
+/// Storage type of the channels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    /// 16-bit floats, enough for most images and half the size
+    #[default]
+    Half,
+    /// 32-bit floats, storing the render exactly
+    Float,
+}
+
+/// Lossless compression of the pixel data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    None,
+    /// Run-length encoding, only effective on flat areas
+    Rle,
+    /// Deflate, over blocks of 16 scanlines
+    #[default]
+    Zip,
+    /// Wavelet compression, usually the best on noisy renders
+    Piz,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExrOptions {
+    pub precision: Precision,
+    pub compression: Compression,
+}
+
+/// Writes the images to an OpenEXR file, as named layers of RGBA channels.
+///
+/// The channels of every layer are prefixed with its name (i.e. `diffuse.R`), except for
+/// the layer with an empty name, whose channels are the plain `R`, `G`, `B` and `A`
+/// that most applications show by default. Every layer must have the same size.
+pub fn write(path: impl AsRef<Path>, layers: &[(&str, &HdrImage)], options: ExrOptions) -> Result<(), ExrError> {
+    return Ok(image(layers, options)?.write().to_file(path)?);
+}
+
+/// Writes the images as an OpenEXR file, as for [`write`]
+pub fn write_to(writer: impl Write + Seek, layers: &[(&str, &HdrImage)], options: ExrOptions) -> Result<(), ExrError> {
+    return Ok(image(layers, options)?.write().to_buffered(writer)?);
+}
+
+fn image(
+    layers: &[(&str, &HdrImage)],
+    options: ExrOptions,
+) -> Result<Image<Layer<AnyChannels<FlatSamples>>>, ExrError> {
+    let (_, first) = layers.first().ok_or(ExrError::Empty)?;
+    let (width, height) = first.dimensions();
+
+    let mut channels = Vec::with_capacity(4 * layers.len());
+    for &(name, image) in layers {
+        if image.dimensions() != (width, height) {
+            return Err(ExrError::Size {
+                name: name.to_owned(),
+                width: image.width(),
+                height: image.height(),
+                expected_width: width,
+                expected_height: height,
+            });
+        }
+
+        for (i, channel) in ["R", "G", "B", "A"].into_iter().enumerate() {
+            let values = image.chunks_exact(4).map(|pixel| pixel[i]);
+            let samples = match options.precision {
+                Precision::Half => FlatSamples::F16(values.map(f16::from_f32).collect()),
+                Precision::Float => FlatSamples::F32(values.collect()),
+            };
+
+            let name = match name.is_empty() {
+                true => channel.to_owned(),
+                false => format!("{name}.{channel}"),
+            };
+            channels.push(AnyChannel::new(name.as_str(), samples));
+        }
+    }
+
+    let size = (width as usize, height as usize);
+    let encoding = Encoding {
+        compression: match options.compression {
+            Compression::None => exr::compression::Compression::Uncompressed,
+            Compression::Rle => exr::compression::Compression::RLE,
+            Compression::Zip => exr::compression::Compression::ZIP16,
+            Compression::Piz => exr::compression::Compression::PIZ,
+        },
+        ..Encoding::default()
+    };
+
+    let layer = Layer::new(
+        size,
+        LayerAttributes::default(),
+        encoding,
+        AnyChannels::sort(channels.into()),
+    );
+    return Ok(Image::new(ImageAttributes::new(IntegerBounds::from_dimensions(size)), layer));
+}
+
+#[cfg(test)]
+#[test]
+fn test_write_exr() {
+    use exr::prelude::{ReadChannels, ReadLayers};
+    use std::io::Cursor;
+
+    let from_fn = |f: fn(u32, u32) -> [f32; 4]| {
+        let pixels = (0..2).flat_map(|y| (0..3).flat_map(move |x| f(x, y))).collect();
+        HdrImage::from_raw(3, 2, pixels).unwrap()
+    };
+    let beauty = from_fn(|x, y| [x as f32 * 10.0, y as f32, 0.25, 1.0]);
+    let depth = from_fn(|x, _| [x as f32; 4]);
+
+    for precision in [Precision::Half, Precision::Float] {
+        for compression in [Compression::None, Compression::Rle, Compression::Zip, Compression::Piz] {
+            let mut buffer = Cursor::new(Vec::new());
+            let options = ExrOptions { precision, compression };
+            write_to(&mut buffer, &[("", &beauty), ("depth", &depth)], options).unwrap();
+
+            buffer.set_position(0);
+            let image = exr::prelude::read()
+                .no_deep_data()
+                .largest_resolution_level()
+                .all_channels()
+                .first_valid_layer()
+                .all_attributes()
+                .from_buffered(buffer)
+                .unwrap();
+
+            let channels = &image.layer_data.channel_data.list;
+            let names = channels.iter().map(|x| x.name.to_string()).collect::<Vec<_>>();
+            assert_eq!(names, ["A", "B", "G", "R", "depth.A", "depth.B", "depth.G", "depth.R"]);
+
+            // values survive the round trip, even past one
+            let red = channels[3].sample_data.values_as_f32().collect::<Vec<_>>();
+            assert_eq!(red, [0.0, 10.0, 20.0, 0.0, 10.0, 20.0]);
+        }
+    }
+
+    assert!(matches!(write_to(Cursor::new(Vec::new()), &[], ExrOptions::default()), Err(ExrError::Empty)));
+}
@@ -0,0 +1,339 @@
+//! Radiance RGBE images (`.hdr`)
+// https://www.graphics.cornell.edu/~bjw/rgbe.html
+
+use crate::display::HdrImage;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum HdrError {
+    #[error("error accessing '{}': {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Read(#[from] std::io::Error),
+    #[error("invalid header: {0}")]
+    Header(String),
+    #[error("invalid data: {0}")]
+    Data(String),
+}
+
+/// Scanlines shorter or longer than this can't be run-length encoded
+const RLE_WIDTH: std::ops::RangeInclusive<usize> = 8..=0x7fff;
+/// Shortest run worth encoding as such
+const MIN_RUN: usize = 4;
+
+/// Loads a Radiance HDR image, with an alpha of one
+pub fn load(path: impl AsRef<Path>) -> Result<HdrImage, HdrError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|source| HdrError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    return parse(BufReader::new(file));
+}
+
+/// Parses a Radiance HDR image, either flat or run-length encoded, with an alpha of one.
+///
+/// Values are divided by the header's exposure, so the image holds the original radiance.
+pub fn parse(mut reader: impl BufRead) -> Result<HdrImage, HdrError> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    if !matches!(line.trim_end(), "#?RADIANCE" | "#?RGBE") {
+        return Err(HdrError::Header("missing magic number".to_owned()));
+    }
+
+    // variables, up to an empty line
+    let mut exposure = 1.0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(HdrError::Header("unexpected end of file".to_owned()));
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        match line.split_once('=') {
+            Some(("FORMAT", "32-bit_rle_rgbe")) => {}
+            Some(("FORMAT", format)) => return Err(HdrError::Header(format!("unsupported format '{format}'"))),
+            Some(("EXPOSURE", value)) => {
+                exposure *= value
+                    .trim()
+                    .parse::<f32>()
+                    .map_err(|e| HdrError::Header(format!("invalid exposure: {e}")))?
+            }
+            _ => {}
+        }
+    }
+
+    line.clear();
+    reader.read_line(&mut line)?;
+    let (width, height, flip) = match line.split_whitespace().collect::<Vec<_>>()[..] {
+        [y @ ("-Y" | "+Y"), height, "+X", width] => {
+            let parse = |x: &str| x.parse::<u32>().map_err(|e| HdrError::Header(format!("invalid size: {e}")));
+            (parse(width)?, parse(height)?, y == "+Y")
+        }
+        _ => return Err(HdrError::Header(format!("unsupported resolution '{}'", line.trim_end()))),
+    };
+
+    // the buffer only grows as scanlines are read, so a truncated file doesn't allocate the whole image
+    let count = super::check_size(width.into(), height.into()).map_err(HdrError::Header)?;
+    let mut pixels = Vec::new();
+    let mut scanline = vec![[0u8; 4]; width as usize];
+    for _ in 0..height {
+        read_scanline(&mut reader, &mut scanline)?;
+        pixels.extend(scanline.iter().flat_map(|&rgbe| {
+            let [r, g, b] = from_rgbe(rgbe).map(|x| x / exposure);
+            [r, g, b, 1.0]
+        }));
+    }
+
+    // bottom-up images
+    if flip {
+        let row = 4 * width as usize;
+        let rows = pixels.chunks_exact(row).rev().flatten().copied().collect();
+        pixels = rows;
+    }
+
+    return HdrImage::from_raw(width, height, pixels.into_boxed_slice())
+        .ok_or_else(|| HdrError::Data(format!("expected {count} pixels")));
+}
+
+/// Saves the image as a run-length encoded Radiance HDR file. Alpha is dropped.
+pub fn write(path: impl AsRef<Path>, image: &HdrImage) -> Result<(), HdrError> {
+    let path = path.as_ref();
+    let io = |source| HdrError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let mut writer = BufWriter::new(File::create(path).map_err(io)?);
+    write_to(&mut writer, image).map_err(io)?;
+    return writer.flush().map_err(io);
+}
+
+/// Encodes the image as a run-length encoded Radiance HDR file. Alpha is dropped.
+pub fn write_to(mut writer: impl Write, image: &HdrImage) -> std::io::Result<()> {
+    let (width, height) = image.dimensions();
+    write!(writer, "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {height} +X {width}\n")?;
+
+    let mut channels = vec![0u8; 4 * width as usize];
+    for row in image.chunks_exact(4 * width as usize) {
+        let scanline = row.chunks_exact(4).map(|x| to_rgbe([x[0], x[1], x[2]]));
+
+        if !RLE_WIDTH.contains(&(width as usize)) {
+            for rgbe in scanline {
+                writer.write_all(&rgbe)?;
+            }
+            continue;
+        }
+
+        // every channel is encoded separately
+        let (r, rest) = channels.split_at_mut(width as usize);
+        let (g, rest) = rest.split_at_mut(width as usize);
+        let (b, e) = rest.split_at_mut(width as usize);
+        for (i, [x, y, z, w]) in scanline.enumerate() {
+            (r[i], g[i], b[i], e[i]) = (x, y, z, w);
+        }
+
+        writer.write_all(&[2, 2, (width >> 8) as u8, width as u8])?;
+        for channel in channels.chunks_exact(width as usize) {
+            write_runs(&mut writer, channel)?;
+        }
+    }
+
+    return Ok(());
+}
+
+fn read_scanline(reader: &mut impl Read, scanline: &mut [[u8; 4]]) -> Result<(), HdrError> {
+    let width = scanline.len();
+    let mut first = [0u8; 4];
+    reader.read_exact(&mut first)?;
+
+    // new run-length encoding, with every channel encoded separately
+    if RLE_WIDTH.contains(&width) && first[0] == 2 && first[1] == 2 && first[2] & 0x80 == 0 {
+        let len = ((first[2] as usize) << 8) | first[3] as usize;
+        if len != width {
+            return Err(HdrError::Data(format!("scanline has {len} pixels, expected {width}")));
+        }
+
+        for channel in 0..4 {
+            let mut i = 0;
+            while i < width {
+                let mut count = [0u8; 1];
+                reader.read_exact(&mut count)?;
+                let (count, run) = match count[0] {
+                    x if x > 128 => ((x - 128) as usize, true),
+                    x => (x as usize, false),
+                };
+
+                if count == 0 || i + count > width {
+                    return Err(HdrError::Data("invalid run length".to_owned()));
+                }
+
+                match run {
+                    true => {
+                        let mut value = [0u8; 1];
+                        reader.read_exact(&mut value)?;
+                        scanline[i..i + count].iter_mut().for_each(|x| x[channel] = value[0]);
+                    }
+                    false => {
+                        let mut values = [0u8; 128];
+                        reader.read_exact(&mut values[..count])?;
+                        scanline[i..i + count].iter_mut().zip(values).for_each(|(x, v)| x[channel] = v);
+                    }
+                }
+                i += count;
+            }
+        }
+
+        return Ok(());
+    }
+
+    // flat pixels, possibly with old-style runs repeating the previous pixel
+    let mut i = 0;
+    let mut shift = 0;
+    let mut rgbe = first;
+    loop {
+        match rgbe {
+            [1, 1, 1, count] if i > 0 => {
+                let count = (count as usize) << shift;
+                if i + count > width {
+                    return Err(HdrError::Data("invalid run length".to_owned()));
+                }
+                let previous = scanline[i - 1];
+                scanline[i..i + count].fill(previous);
+                i += count;
+                shift += 8;
+            }
+            _ => {
+                scanline[i] = rgbe;
+                i += 1;
+                shift = 0;
+            }
+        }
+
+        if i >= width {
+            return Ok(());
+        }
+        reader.read_exact(&mut rgbe)?;
+    }
+}
+
+/// Writes a channel of a scanline, as a sequence of runs and literal bytes
+fn write_runs(writer: &mut impl Write, data: &[u8]) -> std::io::Result<()> {
+    let mut i = 0;
+    while i < data.len() {
+        // look for the next run that's long enough
+        let mut start = i;
+        let mut len = 0;
+        while start < data.len() {
+            len = data[start..].iter().take(127).take_while(|&&x| x == data[start]).count();
+            if len >= MIN_RUN {
+                break;
+            }
+            start += len;
+        }
+
+        // literal bytes up to the run
+        while i < start {
+            let count = usize::min(128, start - i);
+            writer.write_all(&[count as u8])?;
+            writer.write_all(&data[i..i + count])?;
+            i += count;
+        }
+
+        if len >= MIN_RUN {
+            writer.write_all(&[128 + len as u8, data[start]])?;
+            i += len;
+        }
+    }
+
+    return Ok(());
+}
+
+/// Stores the channels as 8-bit mantissas, sharing the exponent of the largest one
+#[inline]
+fn to_rgbe(color: [f32; 3]) -> [u8; 4] {
+    let max = color.into_iter().fold(0.0, f32::max);
+    if !(max >= 1e-32) {
+        return [0; 4];
+    }
+
+    // `max = mantissa * 2^exponent`, with `mantissa` in `[0.5, 1)`. Values too large
+    // for the shared exponent saturate.
+    let exponent = i32::min(((max.to_bits() >> 23) & 0xff) as i32 - 126, 127);
+    let scale = f32::powi(2.0, 8 - exponent);
+    let [r, g, b] = color.map(|x| (x.max(0.0) * scale).min(255.0) as u8);
+    return [r, g, b, (exponent + 128) as u8];
+}
+
+#[inline]
+fn from_rgbe([r, g, b, e]: [u8; 4]) -> [f32; 3] {
+    if e == 0 {
+        return [0.0; 3];
+    }
+    let scale = f32::powi(2.0, e as i32 - (128 + 8));
+    return [r, g, b].map(|x| x as f32 * scale);
+}
+
+#[cfg(test)]
+#[test]
+fn test_hdr() {
+    use std::io::Cursor;
+
+    assert_eq!(to_rgbe([1.0, 0.5, 0.0]), [128, 64, 0, 129]);
+    assert_eq!(from_rgbe([128, 64, 0, 129]), [1.0, 0.5, 0.0]);
+    assert_eq!(to_rgbe([0.0; 3]), [0; 4]);
+    assert_eq!(to_rgbe([f32::INFINITY, 0.0, 0.0]), [255, 0, 0, 255]);
+
+    // flat, and run-length encoded with both runs and literals
+    for width in [5, 40] {
+        let pixels = (0..3 * width)
+            .flat_map(|i| match i % width < 20 {
+                true => [4.0, 2.0, 1.0, 1.0],
+                false => [i as f32, 0.25 * i as f32, 1000.0, 1.0],
+            })
+            .collect();
+        let image = HdrImage::from_raw(width, 3, pixels).unwrap();
+
+        let mut buffer = Vec::new();
+        write_to(&mut buffer, &image).unwrap();
+        if width == 40 {
+            assert!(buffer.len() < 4 * 40 * 3);
+        }
+
+        let result = parse(Cursor::new(buffer)).unwrap();
+        assert_eq!(result.dimensions(), image.dimensions());
+        for (x, y) in result.iter().zip(image.iter()) {
+            // 8 bits of mantissa, relative to the largest channel
+            assert!((x - y).abs() <= 1000.0 / 128.0, "{x} {y}");
+        }
+    }
+
+    // old-style runs, in a bottom-up image
+    let mut file = b"#?RGBE\nEXPOSURE=2\n\n+Y 2 +X 3\n".to_vec();
+    file.extend([128, 0, 0, 129, 1, 1, 1, 2, 0, 128, 0, 129, 0, 0, 0, 0, 0, 0, 0, 0]);
+    let image = parse(Cursor::new(file)).unwrap();
+    assert_eq!(image.get_pixel(0, 0).0, [0.0, 0.5, 0.0, 1.0]);
+    assert_eq!(image.get_pixel(0, 1).0, [0.5, 0.0, 0.0, 1.0]);
+    assert_eq!(image.get_pixel(2, 1).0, [0.5, 0.0, 0.0, 1.0]);
+
+    // malformed sizes are rejected before anything is allocated
+    for size in ["-Y 0 +X 3", "-Y 4294967295 +X 4294967295", "-Y 2000000 +X 1", "-Y 65536 +X 65536"] {
+        let file = format!("#?RADIANCE\n\n{size}\n");
+        assert!(matches!(parse(Cursor::new(file)), Err(HdrError::Header(_))), "{size}");
+    }
+    // and the buffer only grows with the data that's actually there
+    let file = b"#?RADIANCE\n\n-Y 16384 +X 16384\n\x02\x02\x40\x00".to_vec();
+    assert!(matches!(parse(Cursor::new(file)), Err(HdrError::Read(_))));
+}
@@ -0,0 +1,180 @@
+//! Readers and writers for high dynamic range image formats, and saving of renders to any supported format
+
+pub mod exr;
+pub mod hdr;
+pub mod pfm;
+
+use self::{
+    exr::{ExrError, ExrOptions},
+    hdr::HdrError,
+    pfm::PfmError,
+};
+use crate::display::{DisplayTransform, HdrImage};
+use image::{codecs::jpeg::JpegEncoder, ImageFormat};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum OutputError {
+    #[error("unknown image format for '{}'", .0.display())]
+    UnknownFormat(PathBuf),
+    #[error("error writing '{}': {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+    #[error(transparent)]
+    Exr(#[from] ExrError),
+    #[error(transparent)]
+    Hdr(#[from] HdrError),
+    #[error(transparent)]
+    Pfm(#[from] PfmError),
+}
+
+/// Bits per channel of PNG images
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BitDepth {
+    #[default]
+    Eight,
+    Sixteen,
+}
+
+/// Format of a saved image, with its options.
+///
+/// PNG and JPEG images go through a [`DisplayTransform`], while the others store linear radiance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Png { bit_depth: BitDepth },
+    /// JPEG image, with a quality from 1 to 100
+    Jpeg { quality: u8 },
+    Exr(ExrOptions),
+    Hdr,
+    Pfm,
+}
+
+impl OutputFormat {
+    pub const JPEG_QUALITY: u8 = 90;
+
+    /// Default format for the given file extension, case insensitive
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        return Some(match extension.to_ascii_lowercase().as_str() {
+            "png" => Self::Png {
+                bit_depth: BitDepth::default(),
+            },
+            "jpg" | "jpeg" => Self::Jpeg {
+                quality: Self::JPEG_QUALITY,
+            },
+            "exr" => Self::Exr(ExrOptions::default()),
+            "hdr" => Self::Hdr,
+            "pfm" => Self::Pfm,
+            _ => return None,
+        });
+    }
+
+    /// Default format for the path's extension
+    #[inline]
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        return Self::from_extension(path.as_ref().extension()?.to_str()?);
+    }
+}
+
+/// Saves the image, in the default format for the path's extension
+pub fn save(path: impl AsRef<Path>, image: &HdrImage, transform: DisplayTransform) -> Result<(), OutputError> {
+    let path = path.as_ref();
+    let format = OutputFormat::from_path(path).ok_or_else(|| OutputError::UnknownFormat(path.to_path_buf()))?;
+    return save_with_format(path, image, transform, format);
+}
+
+/// Saves the image in the given format, regardless of the path's extension.
+/// `transform` is only used by formats with a low dynamic range.
+pub fn save_with_format(
+    path: impl AsRef<Path>,
+    image: &HdrImage,
+    transform: DisplayTransform,
+    format: OutputFormat,
+) -> Result<(), OutputError> {
+    let path = path.as_ref();
+    match format {
+        OutputFormat::Png {
+            bit_depth: BitDepth::Eight,
+        } => transform.to_rgb8(image).save_with_format(path, ImageFormat::Png)?,
+        OutputFormat::Png {
+            bit_depth: BitDepth::Sixteen,
+        } => transform.to_rgb16(image).save_with_format(path, ImageFormat::Png)?,
+
+        OutputFormat::Jpeg { quality } => {
+            let io = |source| OutputError::Io {
+                path: path.to_path_buf(),
+                source,
+            };
+            let mut writer = BufWriter::new(File::create(path).map_err(io)?);
+            JpegEncoder::new_with_quality(&mut writer, quality.clamp(1, 100)).encode_image(&transform.to_rgb8(image))?;
+            writer.flush().map_err(io)?;
+        }
+
+        OutputFormat::Exr(options) => exr::write(path, &[("", image)], options)?,
+        OutputFormat::Hdr => hdr::write(path, image)?,
+        OutputFormat::Pfm => pfm::write(path, image)?,
+    }
+
+    return Ok(());
+}
+
+/// Widest or tallest image the readers accept
+const MAX_DIMENSION: u64 = 1 << 20;
+/// Largest image the readers accept, in pixels, so that a malformed header can't exhaust memory
+const MAX_PIXELS: u64 = 1 << 28;
+
+/// Checks the size given by an image's header, returning its number of pixels
+pub(crate) fn check_size(width: u64, height: u64) -> Result<usize, String> {
+    if width == 0 || height == 0 {
+        return Err(format!("empty image ({width}x{height})"));
+    }
+
+    let pixels = width.checked_mul(height);
+    return match pixels {
+        Some(pixels) if width <= MAX_DIMENSION && height <= MAX_DIMENSION && pixels <= MAX_PIXELS => {
+            usize::try_from(pixels).map_err(|_| format!("image too large ({width}x{height})"))
+        }
+        _ => Err(format!("image too large ({width}x{height})")),
+    };
+}
+
+#[cfg(test)]
+#[test]
+fn test_save() {
+    let dir = std::env::temp_dir().join(format!("society-test-save-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let pixels = (0..8 * 4).flat_map(|i| [i as f32 / 32.0, 0.5, 2.0, 1.0]).collect();
+    let image = HdrImage::from_raw(8, 4, pixels).unwrap();
+    let transform = DisplayTransform::default();
+
+    for name in ["a.png", "b.JPG", "c.exr", "d.hdr", "e.pfm"] {
+        save(dir.join(name), &image, transform).unwrap();
+    }
+    save_with_format(
+        dir.join("f.png"),
+        &image,
+        transform,
+        OutputFormat::Png {
+            bit_depth: BitDepth::Sixteen,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(image::open(dir.join("a.png")).unwrap().color(), image::ColorType::Rgb8);
+    assert_eq!(image::open(dir.join("b.JPG")).unwrap().color(), image::ColorType::Rgb8);
+    assert_eq!(image::open(dir.join("f.png")).unwrap().color(), image::ColorType::Rgb16);
+    assert_eq!(hdr::load(dir.join("d.hdr")).unwrap().get_pixel(3, 2).0, [19.0 / 32.0, 0.5, 2.0, 1.0]);
+    assert_eq!(pfm::load(dir.join("e.pfm")).unwrap(), image);
+
+    assert!(matches!(save(dir.join("g.txt"), &image, transform), Err(OutputError::UnknownFormat(_))));
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,152 @@
+//! Portable FloatMap images (`.pfm`)
+// https://www.pauldebevec.com/Research/HDR/PFM/
+
+use crate::display::HdrImage;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PfmError {
+    #[error("error accessing '{}': {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Read(#[from] std::io::Error),
+    #[error("invalid header: {0}")]
+    Header(String),
+}
+
+/// Loads a PFM image, with an alpha of one
+pub fn load(path: impl AsRef<Path>) -> Result<HdrImage, PfmError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|source| PfmError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    return parse(BufReader::new(file));
+}
+
+/// Parses a color or grayscale PFM image, with an alpha of one.
+/// Grayscale images are expanded to all three channels.
+pub fn parse(mut reader: impl BufRead) -> Result<HdrImage, PfmError> {
+    let channels = match token(&mut reader)?.as_str() {
+        "PF" => 3,
+        "Pf" => 1,
+        _ => return Err(PfmError::Header("missing magic number".to_owned())),
+    };
+
+    let mut parse = |name: &str| {
+        let token = token(&mut reader)?;
+        token.parse::<f32>().map_err(|e| PfmError::Header(format!("invalid {name} '{token}': {e}")))
+    };
+    let (width, height, scale) = (parse("width")?, parse("height")?, parse("scale")?);
+    let valid = |x: f32| x >= 0.0 && x <= u32::MAX as f32 && x.fract() == 0.0;
+    if !(valid(width) && valid(height)) {
+        return Err(PfmError::Header(format!("invalid size {width}x{height}")));
+    }
+    let count = super::check_size(width as u64, height as u64).map_err(PfmError::Header)?;
+
+    // the sign of the scale gives the byte order, and its magnitude is ignored
+    let (width, height) = (width as u32, height as u32);
+    let decode = match scale < 0.0 {
+        true => f32::from_le_bytes,
+        false => f32::from_be_bytes,
+    };
+
+    let mut row = vec![0u8; 4 * channels * width as usize];
+    let mut rows = Vec::with_capacity(height as usize);
+    for _ in 0..height {
+        reader.read_exact(&mut row)?;
+        let values = row.chunks_exact(4).map(|x| decode([x[0], x[1], x[2], x[3]]));
+        rows.push(match channels {
+            1 => values.flat_map(|x| [x, x, x, 1.0]).collect::<Vec<_>>(),
+            _ => values.collect::<Vec<_>>().chunks_exact(3).flat_map(|x| [x[0], x[1], x[2], 1.0]).collect(),
+        });
+    }
+
+    // rows are stored bottom to top
+    let pixels = rows.into_iter().rev().flatten().collect();
+    return HdrImage::from_raw(width, height, pixels)
+        .ok_or_else(|| PfmError::Header(format!("expected {count} pixels")));
+}
+
+/// Saves the image as a little-endian color PFM file. Alpha is dropped.
+pub fn write(path: impl AsRef<Path>, image: &HdrImage) -> Result<(), PfmError> {
+    let path = path.as_ref();
+    let io = |source| PfmError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let mut writer = BufWriter::new(File::create(path).map_err(io)?);
+    write_to(&mut writer, image).map_err(io)?;
+    return writer.flush().map_err(io);
+}
+
+/// Encodes the image as a little-endian color PFM file. Alpha is dropped.
+pub fn write_to(mut writer: impl Write, image: &HdrImage) -> std::io::Result<()> {
+    let (width, height) = image.dimensions();
+    write!(writer, "PF\n{width} {height}\n-1.0\n")?;
+
+    for row in image.chunks_exact(4 * width as usize).rev() {
+        for pixel in row.chunks_exact(4) {
+            for x in &pixel[..3] {
+                writer.write_all(&x.to_le_bytes())?;
+            }
+        }
+    }
+    return Ok(());
+}
+
+/// Reads a whitespace-delimited header token, along with the single whitespace character after it
+fn token(reader: &mut impl BufRead) -> Result<String, PfmError> {
+    let mut token = String::new();
+    loop {
+        let mut byte = [0u8; 1];
+        reader.read_exact(&mut byte)?;
+        match byte[0] {
+            x if x.is_ascii_whitespace() && token.is_empty() => continue,
+            x if x.is_ascii_whitespace() => return Ok(token),
+            x if x.is_ascii_graphic() && token.len() < 64 => token.push(x as char),
+            _ => return Err(PfmError::Header("unexpected character".to_owned())),
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_pfm() {
+    use std::io::Cursor;
+
+    let pixels = (0..6).flat_map(|i| [i as f32, -0.5, 1e10, 1.0]).collect();
+    let image = HdrImage::from_raw(3, 2, pixels).unwrap();
+
+    let mut buffer = Vec::new();
+    write_to(&mut buffer, &image).unwrap();
+    assert_eq!(buffer.len(), "PF\n3 2\n-1.0\n".len() + 4 * 3 * 6);
+    assert_eq!(parse(Cursor::new(buffer)).unwrap(), image);
+
+    // big-endian grayscale, bottom row first
+    let mut file = b"Pf\n2 2\n1.0\n".to_vec();
+    for x in [1.0f32, 2.0, 3.0, 4.0] {
+        file.extend(x.to_be_bytes());
+    }
+    let image = parse(Cursor::new(file)).unwrap();
+    assert_eq!(image.get_pixel(0, 0).0, [3.0, 3.0, 3.0, 1.0]);
+    assert_eq!(image.get_pixel(1, 1).0, [2.0, 2.0, 2.0, 1.0]);
+
+    // malformed sizes are rejected before anything is allocated
+    for size in ["0 2", "1e30 1", "-1 2", "1.5 2", "4294967295 4294967295", "2000000 1", "65536 65536"] {
+        let file = format!("PF\n{size}\n-1.0\n");
+        assert!(matches!(parse(Cursor::new(file)), Err(PfmError::Header(_))), "{size}");
+    }
+    // and the rows are only allocated as they're read
+    let file = b"PF\n16384 16384\n-1.0\n\0\0\0\0".to_vec();
+    assert!(matches!(parse(Cursor::new(file)), Err(PfmError::Read(_))));
+}
@@ -0,0 +1,7 @@
+use crate::{material::Lambertian, math::Vec3};
+
+pub mod obj;
+pub mod ply;
+
+/// Material of imported geometry that doesn't specify any
+pub const DEFAULT_MATERIAL: Lambertian = Lambertian::new(Vec3::splat(0.8));
@@ -0,0 +1,658 @@
+use super::DEFAULT_MATERIAL;
+use crate::{
+    element::Element,
+    material::{BlinnPhong, Dielectric, DynMaterial, Emissive, Lambertian, Principled},
+    math::{UnitVec3, Vec2, Vec3},
+    object::{mesh::TriangleMesh, DynObject},
+};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    str::SplitWhitespace,
+    sync::Arc,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ObjError {
+    #[error("error reading '{}': {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Read(#[from] std::io::Error),
+    #[error("line {line}: {message}")]
+    Syntax { line: usize, message: String },
+}
+
+/// Loads a Wavefront OBJ file, along with the material libraries it references.
+///
+/// Every object, group and material change in the file becomes a separate [`TriangleMesh`] element.
+/// Libraries that are missing or can't be read are skipped, as are the materials they would define.
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<Element<DynObject<'static>>>, ObjError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|source| ObjError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let base = path.parent().unwrap_or(Path::new(""));
+    return parse(BufReader::new(file), |name| {
+        let path = base.join(name);
+        let file = File::open(&path).map_err(|source| ObjError::Io { path, source })?;
+        parse_mtl(BufReader::new(file))
+    });
+}
+
+/// Parses an OBJ file, calling `mtllib` to load each material library it references.
+///
+/// I/O errors returned by `mtllib` skip the library, and the meshes using its materials get the default one.
+pub fn parse(
+    reader: impl BufRead,
+    mut mtllib: impl FnMut(&str) -> Result<HashMap<String, MtlMaterial>, ObjError>,
+) -> Result<Vec<Element<DynObject<'static>>>, ObjError> {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut materials = HashMap::new();
+
+    let mut elements = Vec::new();
+    let default_material: DynMaterial = Arc::new(DEFAULT_MATERIAL);
+    let mut mesh = MeshBuilder::new(default_material.clone());
+
+    for line in logical_lines(reader) {
+        let (line, content) = line?;
+        let syntax = |message: String| ObjError::Syntax { line, message };
+
+        let mut args = content.split_whitespace();
+        match args.next() {
+            Some("v") => positions.push(Vec3::from_array(parse_floats(&mut args, 3).map_err(syntax)?)),
+            Some("vn") => {
+                // degenerate normals are dropped, which leaves their mesh with the geometric ones
+                let normal = Vec3::from_array(parse_floats(&mut args, 3).map_err(syntax)?);
+                normals.push(normal.sq_norm().is_normal().then(|| normal.unit()))
+            }
+            Some("vt") => {
+                let [u, v, _] = parse_floats(&mut args, 1).map_err(syntax)?;
+                uvs.push(Vec2::new(u, v))
+            }
+
+            Some("f") => {
+                let face = args
+                    .map(|vertex| parse_vertex(vertex, [positions.len(), uvs.len(), normals.len()]))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(syntax)?;
+
+                if face.len() < 3 {
+                    return Err(syntax(format!("face has {} vertices, expected at least 3", face.len())));
+                }
+
+                let face = face
+                    .into_iter()
+                    .map(|(p, t, n)| mesh.vertex(&positions, &uvs, &normals, p, t, n))
+                    .collect::<Vec<_>>();
+
+                // triangulate polygons as a fan around the first vertex
+                for j in 1..face.len() - 1 {
+                    mesh.indices.push([face[0], face[j], face[j + 1]]);
+                }
+            }
+
+            Some("o" | "g") => {
+                let material = mesh.material.clone();
+                elements.extend(mesh.build());
+                mesh = MeshBuilder::new(material);
+            }
+
+            Some("usemtl") => {
+                let name = args.next().ok_or_else(|| syntax("missing material name".into()))?;
+                let material = materials.get(name).unwrap_or(&default_material).clone();
+                elements.extend(mesh.build());
+                mesh = MeshBuilder::new(material);
+            }
+
+            Some("mtllib") => {
+                // the rest of the line is a single path, which may contain spaces
+                let name = content.trim().split_once(char::is_whitespace).map_or("", |(_, name)| name.trim_start());
+                if name.is_empty() {
+                    return Err(syntax("missing material library".into()));
+                }
+
+                // materials are shared by every mesh using them
+                match mtllib(name) {
+                    Ok(library) => materials.extend(library.into_iter().map(|(name, x)| (name, x.to_material()))),
+                    Err(ObjError::Io { .. } | ObjError::Read(_)) => {}
+                    Err(error) => return Err(error),
+                }
+            }
+
+            // smoothing groups, lines, points and free-form geometry aren't supported
+            _ => {}
+        }
+    }
+
+    elements.extend(mesh.build());
+    return Ok(elements);
+}
+
+/// Parameters of physically based MTL materials that don't specify them
+const PRINCIPLED: Principled = Principled::new(DEFAULT_MATERIAL.albedo);
+
+/// Material as described by an MTL library
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MtlMaterial {
+    /// Diffuse color (`Kd`)
+    pub diffuse: Vec3,
+    /// Specular color (`Ks`)
+    pub specular: Vec3,
+    /// Specular exponent (`Ns`)
+    pub shininess: f32,
+    /// Index of refraction (`Ni`)
+    pub ior: f32,
+    /// Opacity (`d`, or one minus `Tr`)
+    pub dissolve: f32,
+    /// Emitted radiance (`Ke`)
+    pub emission: Vec3,
+    /// Roughness (`Pr`), from the physically based extension of the format, like the parameters that follow
+    pub roughness: Option<f32>,
+    /// Metalness (`Pm`)
+    pub metallic: Option<f32>,
+    /// Sheen (`Ps`)
+    pub sheen: Option<f32>,
+    /// Clearcoat thickness (`Pc`)
+    pub clearcoat: Option<f32>,
+    /// Clearcoat roughness (`Pcr`)
+    pub clearcoat_roughness: Option<f32>,
+    /// Anisotropy (`aniso`)
+    pub anisotropy: Option<f32>,
+}
+
+impl Default for MtlMaterial {
+    #[inline]
+    fn default() -> Self {
+        return Self {
+            diffuse: DEFAULT_MATERIAL.albedo,
+            specular: Vec3::ZERO,
+            shininess: 1.0,
+            ior: Dielectric::GLASS.ior,
+            dissolve: 1.0,
+            emission: Vec3::ZERO,
+            roughness: None,
+            metallic: None,
+            sheen: None,
+            clearcoat: None,
+            clearcoat_roughness: None,
+            anisotropy: None,
+        };
+    }
+}
+
+impl MtlMaterial {
+    /// `true` if the material uses any parameter of the physically based extension of the format
+    #[inline]
+    pub fn is_physically_based(&self) -> bool {
+        return [self.roughness, self.metallic, self.sheen, self.clearcoat, self.clearcoat_roughness, self.anisotropy]
+            .iter()
+            .any(Option::is_some);
+    }
+
+    /// Closest material to the description: a principled material if it uses the physically based
+    /// extension, a dielectric if it's transparent, Blinn-Phong if it has a specular color, and
+    /// Lambertian otherwise. Emission is added on top of the others.
+    pub fn to_material(&self) -> DynMaterial {
+        if self.is_physically_based() {
+            return Arc::new(self.to_principled());
+        }
+
+        let emission = self.emission;
+        let with_emission = |material: DynMaterial| -> DynMaterial {
+            match emission == Vec3::ZERO {
+                true => material,
+                false => Arc::new(Emissive::new(material, emission)),
+            }
+        };
+
+        if self.dissolve < 1.0 {
+            return with_emission(Arc::new(Dielectric::new(self.ior)));
+        }
+        if self.specular == Vec3::ZERO {
+            return with_emission(Arc::new(Lambertian::new(self.diffuse)));
+        }
+        return with_emission(Arc::new(BlinnPhong::new(self.diffuse, self.specular, self.shininess)));
+    }
+
+    /// Principled material with the description's parameters. Without a roughness, the specular
+    /// exponent gives the one of a lobe of the same width, if the material has a specular color.
+    fn to_principled(&self) -> Principled {
+        let has_specular = self.specular != Vec3::ZERO;
+        // "Microfacet Models for Refraction through Rough Surfaces" (Walter et al., 2007)
+        let from_shininess = || f32::powf(2.0 / (self.shininess.max(0.0) + 2.0), 0.25);
+        let roughness = match self.roughness {
+            Some(roughness) => roughness,
+            None if has_specular => from_shininess(),
+            None => PRINCIPLED.roughness,
+        };
+        let specular = match has_specular {
+            true => self.specular.to_array().into_iter().fold(0.0, f32::max).min(1.0),
+            false => PRINCIPLED.specular,
+        };
+
+        return Principled {
+            base_color: self.diffuse,
+            metallic: self.metallic.unwrap_or(PRINCIPLED.metallic),
+            roughness,
+            anisotropy: self.anisotropy.unwrap_or(PRINCIPLED.anisotropy),
+            specular,
+            ior: self.ior,
+            sheen: self.sheen.unwrap_or(PRINCIPLED.sheen),
+            clearcoat: self.clearcoat.unwrap_or(PRINCIPLED.clearcoat),
+            clearcoat_roughness: self.clearcoat_roughness.unwrap_or(PRINCIPLED.clearcoat_roughness),
+            transmission: 1.0 - self.dissolve,
+            emission: self.emission,
+            ..PRINCIPLED
+        };
+    }
+}
+
+/// Parses an MTL material library, returning every material by name
+pub fn parse_mtl(reader: impl BufRead) -> Result<HashMap<String, MtlMaterial>, ObjError> {
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MtlMaterial)> = None;
+
+    for line in logical_lines(reader) {
+        let (line, content) = line?;
+        let syntax = |message: String| ObjError::Syntax { line, message };
+
+        let mut args = content.split_whitespace();
+        let keyword = args.next();
+        if let Some("newmtl") = keyword {
+            let name = args.next().ok_or_else(|| syntax("missing material name".into()))?;
+            materials.extend(current.replace((name.to_string(), MtlMaterial::default())));
+            continue;
+        }
+
+        let Some((_, material)) = current.as_mut() else { continue };
+        match keyword {
+            Some("Kd") => material.diffuse = parse_color(&mut args).map_err(syntax)?,
+            Some("Ks") => material.specular = parse_color(&mut args).map_err(syntax)?,
+            Some("Ns") => material.shininess = parse_floats(&mut args, 1).map_err(syntax)?[0],
+            Some("Ni") => material.ior = parse_floats(&mut args, 1).map_err(syntax)?[0],
+            Some("d") => material.dissolve = parse_floats(&mut args, 1).map_err(syntax)?[0],
+            Some("Tr") => material.dissolve = 1.0 - parse_floats(&mut args, 1).map_err(syntax)?[0],
+            Some("Ke") => material.emission = parse_color(&mut args).map_err(syntax)?,
+            Some(keyword @ ("Pr" | "Pm" | "Ps" | "Pc" | "Pcr" | "aniso")) => {
+                let value = Some(parse_floats(&mut args, 1).map_err(syntax)?[0]);
+                match keyword {
+                    "Pr" => material.roughness = value,
+                    "Pm" => material.metallic = value,
+                    "Ps" => material.sheen = value,
+                    "Pc" => material.clearcoat = value,
+                    "Pcr" => material.clearcoat_roughness = value,
+                    _ => material.anisotropy = value,
+                }
+            }
+            // other properties and texture maps aren't supported by the material model
+            _ => {}
+        }
+    }
+
+    materials.extend(current);
+    return Ok(materials);
+}
+
+struct MeshBuilder {
+    material: DynMaterial,
+    /// Maps each distinct combination of position, uv and normal indices to its vertex
+    vertices: HashMap<(usize, Option<usize>, Option<usize>), u32>,
+    positions: Vec<Vec3>,
+    uvs: Vec<Option<Vec2>>,
+    normals: Vec<Option<UnitVec3>>,
+    indices: Vec<[u32; 3]>,
+}
+
+impl MeshBuilder {
+    fn new(material: DynMaterial) -> Self {
+        return Self {
+            material,
+            vertices: HashMap::new(),
+            positions: Vec::new(),
+            uvs: Vec::new(),
+            normals: Vec::new(),
+            indices: Vec::new(),
+        };
+    }
+
+    fn vertex(
+        &mut self,
+        positions: &[Vec3],
+        uvs: &[Vec2],
+        normals: &[Option<UnitVec3>],
+        p: usize,
+        t: Option<usize>,
+        n: Option<usize>,
+    ) -> u32 {
+        return *self.vertices.entry((p, t, n)).or_insert_with(|| {
+            self.positions.push(positions[p]);
+            self.uvs.push(t.map(|t| uvs[t]));
+            self.normals.push(n.and_then(|n| normals[n]));
+            (self.positions.len() - 1) as u32
+        });
+    }
+
+    fn build(self) -> Option<Element<DynObject<'static>>> {
+        if self.indices.is_empty() {
+            return None;
+        }
+
+        let mut mesh = TriangleMesh::new(self.positions, self.indices);
+        // attributes are only kept if every vertex has them
+        if let Some(uvs) = self.uvs.into_iter().collect::<Option<Vec<_>>>() {
+            mesh = mesh.with_uvs(uvs);
+        }
+        if let Some(normals) = self.normals.into_iter().collect::<Option<Vec<_>>>() {
+            mesh = mesh.with_normals(normals);
+        }
+
+        return Some(Element::new_unzise(mesh, self.material));
+    }
+}
+
+/// Iterates over the non-empty lines of the file, along with their line number.
+/// Comments are stripped, and lines ending with a backslash are joined with the next one.
+fn logical_lines(reader: impl BufRead) -> impl Iterator<Item = Result<(usize, String), ObjError>> {
+    let mut lines = reader.lines().enumerate();
+    return std::iter::from_fn(move || {
+        let mut result = String::new();
+        let mut start = None;
+
+        for (i, line) in lines.by_ref() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e.into())),
+            };
+
+            let line = line.split('#').next().unwrap_or_default().trim_end();
+            start.get_or_insert(i + 1);
+
+            match line.strip_suffix('\\') {
+                Some(line) => {
+                    result.push_str(line);
+                    result.push(' ');
+                }
+                None => {
+                    result.push_str(line);
+                    if !result.trim().is_empty() {
+                        return Some(Ok((start.unwrap_or(i + 1), result)));
+                    }
+                    result.clear();
+                    start = None;
+                }
+            }
+        }
+
+        if result.trim().is_empty() {
+            return None;
+        }
+        return Some(Ok((start.unwrap_or_default(), result)));
+    });
+}
+
+/// Parses up to three floats, requiring at least `min` of them. Missing values default to zero.
+fn parse_floats(args: &mut SplitWhitespace, min: usize) -> Result<[f32; 3], String> {
+    let mut result = [0.0; 3];
+    for i in 0..3 {
+        match args.next() {
+            Some(arg) => result[i] = arg.parse().map_err(|e| format!("invalid number '{arg}': {e}"))?,
+            None if i < min => return Err(format!("expected at least {min} values, found {i}")),
+            None => break,
+        }
+    }
+    return Ok(result);
+}
+
+/// Parses an RGB color, where a single value stands for a shade of gray
+fn parse_color(args: &mut SplitWhitespace) -> Result<Vec3, String> {
+    let mut peek = args.clone();
+    if let Some("spectral" | "xyz") = peek.next() {
+        return Err("only rgb colors are supported".into());
+    }
+
+    return match (args.clone().count(), parse_floats(args, 1)?) {
+        (1, [x, _, _]) => Ok(Vec3::splat(x)),
+        (_, [r, g, b]) => Ok(Vec3::new(r, g, b)),
+    };
+}
+
+/// Parses a face vertex (`v`, `v/vt`, `v//vn` or `v/vt/vn`), resolving its one-based
+/// (or negative, relative to the end) indices with the number of elements read so far.
+fn parse_vertex(vertex: &str, len: [usize; 3]) -> Result<(usize, Option<usize>, Option<usize>), String> {
+    let mut parts = vertex.split('/');
+    let mut indices = [None; 3];
+
+    for (i, len) in len.into_iter().enumerate() {
+        let part = match parts.next() {
+            Some("") | None if i > 0 => continue,
+            Some(part) => part,
+            None => return Err(format!("invalid face vertex '{vertex}'")),
+        };
+
+        let index = part
+            .parse::<isize>()
+            .map_err(|e| format!("invalid index '{part}': {e}"))?;
+
+        let resolved = match index {
+            1.. => index - 1,
+            ..=-1 => len as isize + index,
+            0 => return Err("indices start at 1".into()),
+        };
+
+        if resolved < 0 || resolved as usize >= len {
+            return Err(format!("index {index} out of range"));
+        }
+        indices[i] = Some(resolved as usize);
+    }
+
+    if parts.next().is_some() {
+        return Err(format!("invalid face vertex '{vertex}'"));
+    }
+
+    let [p, t, n] = indices;
+    return Ok((p.ok_or_else(|| format!("invalid face vertex '{vertex}'"))?, t, n));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, parse_mtl, MtlMaterial, ObjError};
+    use crate::{
+        import::DEFAULT_MATERIAL,
+        material::Material,
+        math::{Vec2, Vec3},
+        object::{Object, Ray},
+    };
+    use std::{collections::HashMap, f32::consts::PI, io, path::PathBuf};
+
+    const MTL: &str = "
+        newmtl red
+        Kd 1 0 0
+        Ks 0.5
+        Ns 20
+
+        newmtl blue
+        Kd 0 0 1
+
+        newmtl glass
+        Ni 1.33
+        Tr 1
+
+        newmtl gold
+        Kd 1 0.8 0.3
+        Pm 1
+        Pr 0.2
+        Ke 0 0 0.5
+    ";
+
+    const OBJ: &str = "
+        # unit square on the xy plane, and a triangle behind it
+        mtllib scene.mtl
+        v 0 0 0
+        v 1 0 0
+        v 1 1 0
+        v 0 1 0
+        vn 0 0 1
+
+        o square
+        usemtl red
+        f 1//1 2//1 3//1 4//1
+
+        o triangle
+        usemtl blue
+        v 0 0 -1
+        v 1 0 -1
+        v 0 1 -1
+        f -3 -2 \\
+          -1
+    ";
+
+    #[test]
+    fn test_parse_mtl() {
+        let materials = parse_mtl(MTL.as_bytes()).unwrap();
+        assert_eq!(materials.len(), 4);
+        assert_eq!(materials["red"].diffuse, Vec3::new(1.0, 0.0, 0.0));
+        assert_eq!(materials["red"].specular, Vec3::splat(0.5));
+        assert_eq!(materials["red"].shininess, 20.0);
+        assert_eq!(materials["blue"].shininess, 1.0);
+        assert_eq!(materials["blue"].diffuse, Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(materials["blue"].dissolve, 1.0);
+        assert_eq!(materials["glass"].ior, 1.33);
+        assert_eq!(materials["glass"].dissolve, 0.0);
+        assert!(!materials["glass"].is_physically_based());
+        assert!(materials["gold"].is_physically_based());
+        assert_eq!(materials["gold"].metallic, Some(1.0));
+        assert_eq!(materials["gold"].roughness, Some(0.2));
+        assert_eq!(materials["gold"].emission, Vec3::new(0.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn test_mtl_emission() {
+        let materials = parse_mtl(
+            "
+            newmtl lamp
+            Kd 0.5 0.5 0.5
+            Ke 4 4 4
+
+            newmtl shiny_lamp
+            Kd 0.5 0 0
+            Ks 0.5
+            Ns 50
+            Ke 1 0 0
+
+            newmtl glowing_glass
+            d 0.5
+            Ke 0 0 1
+
+            newmtl metal
+            Kd 1 0.8 0.3
+            Ks 0.5
+            Ns 1000
+            Pm 1
+            "
+            .as_bytes(),
+        )
+        .unwrap();
+
+        // emission is carried on top of the material the description maps to without it
+        let hit = crate::material::tests::hit();
+        let (wo, wi) = (Vec3::new(0.6, 0.0, 0.8), Vec3::new(0.0, 0.6, 0.8));
+        for name in ["lamp", "shiny_lamp", "glowing_glass"] {
+            let description = materials[name];
+            let material = description.to_material();
+            let plain = MtlMaterial {
+                emission: Vec3::ZERO,
+                ..description
+            }
+            .to_material();
+
+            assert_eq!(material.emitted(&hit, wo), description.emission);
+            assert_eq!(material.eval(&hit, wo, wi), plain.eval(&hit, wo, wi));
+            assert_eq!(material.sample(&hit, wo, Vec2::new(0.3, 0.7)), plain.sample(&hit, wo, Vec2::new(0.3, 0.7)));
+        }
+        assert_eq!(materials["lamp"].to_material().eval(&hit, wo, wi), Vec3::splat(0.5 / PI));
+        assert!(materials["glowing_glass"].to_material().sample(&hit, wo, Vec2::ZERO).unwrap().specular);
+
+        // physically based materials take their specular layer from `Ks` and `Ns`
+        let metal = materials["metal"].to_principled();
+        assert_eq!(metal.specular, 0.5);
+        assert!((metal.roughness - f32::powf(2.0 / 1002.0, 0.25)).abs() < 1e-6);
+        assert_eq!(metal.metallic, 1.0);
+    }
+
+    #[test]
+    fn test_parse_obj() {
+        let elements = parse(OBJ.as_bytes(), |name| {
+            assert_eq!(name, "scene.mtl");
+            parse_mtl(MTL.as_bytes())
+        })
+        .unwrap();
+
+        assert_eq!(elements.len(), 2);
+
+        let ray = Ray::new(Vec3::new(0.25, 0.75, 1.0), Vec3::new(0.0, 0.0, -1.0).unit());
+        let hits = elements.iter().map(|x| x.object.hit(ray, 0.0, f32::INFINITY).unwrap()).collect::<Vec<_>>();
+        assert_eq!(hits[0].t, 1.0);
+        assert_eq!(hits[1].t, 2.0);
+
+        let wo = Vec3::new(0.0, 0.0, 1.0);
+        assert_eq!(elements[0].material.albedo(&hits[0], wo), Vec3::new(1.0, 0.0, 0.0));
+        assert_eq!(elements[1].material.albedo(&hits[1], wo), Vec3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_missing_mtllib() {
+        let obj = OBJ.replace("mtllib scene.mtl", "mtllib my scene.mtl");
+        let elements = parse(obj.as_bytes(), |name| {
+            assert_eq!(name, "my scene.mtl");
+            Err(ObjError::Io {
+                path: PathBuf::from(name),
+                source: io::ErrorKind::NotFound.into(),
+            })
+        })
+        .unwrap();
+
+        // the import goes on with the default material, as for unknown material names
+        let ray = Ray::new(Vec3::new(0.25, 0.75, 1.0), Vec3::new(0.0, 0.0, -1.0).unit());
+        let hit = elements[0].object.hit(ray, 0.0, f32::INFINITY).unwrap();
+        let albedo = elements[0].material.albedo(&hit, Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(albedo, DEFAULT_MATERIAL.albedo);
+
+        // while malformed libraries still fail it
+        let result = parse(OBJ.as_bytes(), |_| parse_mtl("newmtl red\nKd 1 x 0".as_bytes()));
+        assert!(matches!(result, Err(ObjError::Syntax { .. })));
+    }
+
+    #[test]
+    fn test_degenerate_normal() {
+        let obj = "
+            v 0 0 0
+            v 1 0 0
+            v 0 1 0
+            vn 0 0 1
+            vn 0 0 0
+            f 1//1 2//2 3//1
+        ";
+        let elements = parse(obj.as_bytes(), |_| Ok(HashMap::new())).unwrap();
+
+        // the mesh falls back to its geometric normals, instead of interpolating a NaN
+        let ray = Ray::new(Vec3::new(0.25, 0.25, 1.0), Vec3::new(0.0, 0.0, -1.0).unit());
+        let hit = elements[0].object.hit(ray, 0.0, f32::INFINITY).unwrap();
+        assert_eq!(hit.normal, Vec3::new(0.0, 0.0, 1.0).unit());
+    }
+
+    #[test]
+    fn test_invalid_index() {
+        let result = parse("v 0 0 0\nf 1 2 3".as_bytes(), |_| Ok(HashMap::new()));
+        assert!(result.is_err());
+    }
+}
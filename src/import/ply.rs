@@ -0,0 +1,504 @@
+use crate::{
+    math::{Vec2, Vec3},
+    object::mesh::TriangleMesh,
+};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PlyError {
+    #[error("error reading '{}': {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Read(#[from] std::io::Error),
+    #[error("invalid header: {0}")]
+    Header(String),
+    #[error("invalid data: {0}")]
+    Data(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Property {
+    Scalar(String, Scalar),
+    List { name: String, len: Scalar, item: Scalar },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ElementDef {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// Loads a Stanford PLY mesh.
+///
+/// Vertex normals (`nx`, `ny`, `nz`), colors (`red`, `green`, `blue`) and uv coordinates
+/// (`u`/`v`, `s`/`t` or `texture_u`/`texture_v`) are kept if present. Polygons are triangulated as fans.
+pub fn load(path: impl AsRef<Path>) -> Result<TriangleMesh, PlyError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|source| PlyError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    return parse(BufReader::new(file));
+}
+
+/// Parses an ASCII or binary PLY mesh
+pub fn parse(mut reader: impl BufRead) -> Result<TriangleMesh, PlyError> {
+    let (format, elements) = parse_header(&mut reader)?;
+    let mut reader = ValueReader::new(reader, format);
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut colors = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+
+    for element in elements.iter() {
+        match element.name.as_str() {
+            "vertex" => {
+                let find = |names: &[&str]| {
+                    names.iter().find_map(|name| {
+                        element.properties.iter().position(|x| matches!(x, Property::Scalar(n, _) if n == name))
+                    })
+                };
+
+                let position = [find(&["x"]), find(&["y"]), find(&["z"])];
+                let normal = [find(&["nx"]), find(&["ny"]), find(&["nz"])];
+                let color = [find(&["red", "r"]), find(&["green", "g"]), find(&["blue", "b"])];
+                let uv = [
+                    find(&["u", "s", "texture_u", "texture_s"]),
+                    find(&["v", "t", "texture_v", "texture_t"]),
+                ];
+
+                let [Some(x), Some(y), Some(z)] = position else {
+                    return Err(PlyError::Header("vertices have no position".into()));
+                };
+
+                // integer colors are normalized to the [0, 1] range
+                let color_scale = match color[0].map(|i| &element.properties[i]) {
+                    Some(Property::Scalar(_, Scalar::F32 | Scalar::F64)) => 1.0,
+                    Some(Property::Scalar(_, Scalar::U16)) => 1.0 / u16::MAX as f32,
+                    _ => 1.0 / u8::MAX as f32,
+                };
+
+                let mut row = Vec::with_capacity(element.properties.len());
+                for _ in 0..element.count {
+                    row.clear();
+                    for property in element.properties.iter() {
+                        match property {
+                            Property::Scalar(_, ty) => row.push(reader.read(*ty)? as f32),
+                            Property::List { len, item, .. } => reader.skip_list(*len, *item)?,
+                        }
+                    }
+
+                    positions.push(Vec3::new(row[x], row[y], row[z]));
+                    if let [Some(x), Some(y), Some(z)] = normal {
+                        let normal = Vec3::new(row[x], row[y], row[z]);
+                        normals.push(normal.sq_norm().is_normal().then(|| normal.unit()));
+                    }
+                    if let [Some(r), Some(g), Some(b)] = color {
+                        colors.push(color_scale * Vec3::new(row[r], row[g], row[b]));
+                    }
+                    if let [Some(u), Some(v)] = uv {
+                        uvs.push(Vec2::new(row[u], row[v]));
+                    }
+                }
+            }
+
+            "face" => {
+                let mut face = Vec::new();
+                for _ in 0..element.count {
+                    for property in element.properties.iter() {
+                        match property {
+                            Property::List { name, len, item } if name == "vertex_indices" || name == "vertex_index" => {
+                                let count = reader.read_index(*len)?;
+                                face.clear();
+                                for _ in 0..count {
+                                    face.push(reader.read_index(*item)?);
+                                }
+                            }
+                            Property::List { len, item, .. } => reader.skip_list(*len, *item)?,
+                            Property::Scalar(_, ty) => {
+                                reader.read(*ty)?;
+                            }
+                        }
+                    }
+
+                    if face.len() < 3 {
+                        return Err(PlyError::Data(format!("face has {} vertices, expected at least 3", face.len())));
+                    }
+                    for j in 1..face.len() - 1 {
+                        indices.push([face[0], face[j], face[j + 1]]);
+                    }
+                }
+            }
+
+            // other elements (edges, materials, ...) are skipped
+            _ => {
+                for _ in 0..element.count {
+                    for property in element.properties.iter() {
+                        match property {
+                            Property::Scalar(_, ty) => {
+                                reader.read(*ty)?;
+                            }
+                            Property::List { len, item, .. } => reader.skip_list(*len, *item)?,
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(index) = indices.iter().flatten().find(|&&i| i as usize >= positions.len()) {
+        return Err(PlyError::Data(format!("vertex index {index} out of range")));
+    }
+
+    let vertices = positions.len();
+    let mut mesh = TriangleMesh::new(positions, indices);
+    // degenerate normals leave the mesh with its geometric ones
+    if let Some(normals) = normals.into_iter().collect::<Option<Vec<_>>>() {
+        if !normals.is_empty() && normals.len() == vertices {
+            mesh = mesh.with_normals(normals);
+        }
+    }
+    if !colors.is_empty() && colors.len() == vertices {
+        mesh = mesh.with_colors(colors);
+    }
+    if !uvs.is_empty() && uvs.len() == vertices {
+        mesh = mesh.with_uvs(uvs);
+    }
+
+    return Ok(mesh);
+}
+
+fn parse_header(reader: &mut impl BufRead) -> Result<(Format, Vec<ElementDef>), PlyError> {
+    let mut line = String::new();
+    let mut next_line = |line: &mut String| -> Result<bool, PlyError> {
+        line.clear();
+        return Ok(reader.read_line(line)? > 0);
+    };
+
+    if !next_line(&mut line)? || line.trim_end() != "ply" {
+        return Err(PlyError::Header("missing 'ply' magic number".into()));
+    }
+
+    let mut format = None;
+    let mut elements = Vec::<ElementDef>::new();
+
+    loop {
+        if !next_line(&mut line)? {
+            return Err(PlyError::Header("missing 'end_header'".into()));
+        }
+
+        let mut args = line.split_whitespace();
+        match args.next() {
+            Some("format") => {
+                format = Some(match args.next() {
+                    Some("ascii") => Format::Ascii,
+                    Some("binary_little_endian") => Format::BinaryLittleEndian,
+                    Some("binary_big_endian") => Format::BinaryBigEndian,
+                    other => return Err(PlyError::Header(format!("unknown format {other:?}"))),
+                })
+            }
+
+            Some("element") => {
+                let (Some(name), Some(count)) = (args.next(), args.next()) else {
+                    return Err(PlyError::Header(format!("invalid element '{}'", line.trim_end())));
+                };
+                let count = count
+                    .parse()
+                    .map_err(|e| PlyError::Header(format!("invalid element count '{count}': {e}")))?;
+
+                elements.push(ElementDef {
+                    name: name.to_string(),
+                    count,
+                    properties: Vec::new(),
+                })
+            }
+
+            Some("property") => {
+                let Some(element) = elements.last_mut() else {
+                    return Err(PlyError::Header("property defined outside of an element".into()));
+                };
+
+                let args = args.collect::<Vec<_>>();
+                let property = match args[..] {
+                    ["list", len, item, name] => Property::List {
+                        name: name.to_string(),
+                        len: parse_scalar(len)?,
+                        item: parse_scalar(item)?,
+                    },
+                    [ty, name] => Property::Scalar(name.to_string(), parse_scalar(ty)?),
+                    _ => return Err(PlyError::Header(format!("invalid property '{}'", line.trim_end()))),
+                };
+                element.properties.push(property)
+            }
+
+            Some("end_header") => break,
+            Some("comment" | "obj_info") | None => {}
+            Some(other) => return Err(PlyError::Header(format!("unknown keyword '{other}'"))),
+        }
+    }
+
+    let format = format.ok_or_else(|| PlyError::Header("missing format".into()))?;
+    return Ok((format, elements));
+}
+
+fn parse_scalar(name: &str) -> Result<Scalar, PlyError> {
+    return Ok(match name {
+        "char" | "int8" => Scalar::I8,
+        "uchar" | "uint8" => Scalar::U8,
+        "short" | "int16" => Scalar::I16,
+        "ushort" | "uint16" => Scalar::U16,
+        "int" | "int32" => Scalar::I32,
+        "uint" | "uint32" => Scalar::U32,
+        "float" | "float32" => Scalar::F32,
+        "double" | "float64" => Scalar::F64,
+        _ => return Err(PlyError::Header(format!("unknown property type '{name}'"))),
+    });
+}
+
+impl Scalar {
+    #[inline]
+    fn size(self) -> usize {
+        return match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        };
+    }
+}
+
+/// Reads property values in either of the formats
+struct ValueReader<R> {
+    reader: R,
+    format: Format,
+    line: String,
+    position: usize,
+}
+
+impl<R: BufRead> ValueReader<R> {
+    fn new(reader: R, format: Format) -> Self {
+        return Self {
+            reader,
+            format,
+            line: String::new(),
+            position: 0,
+        };
+    }
+
+    fn read(&mut self, ty: Scalar) -> Result<f64, PlyError> {
+        if self.format == Format::Ascii {
+            let token = self.next_token()?;
+            return token
+                .parse::<f64>()
+                .map_err(|e| PlyError::Data(format!("invalid number '{token}': {e}")));
+        }
+
+        let mut bytes = [0; 8];
+        let bytes = &mut bytes[..ty.size()];
+        self.reader.read_exact(bytes)?;
+        if self.format == Format::BinaryBigEndian {
+            bytes.reverse();
+        }
+
+        return Ok(match ty {
+            Scalar::I8 => i8::from_le_bytes([bytes[0]]) as f64,
+            Scalar::U8 => bytes[0] as f64,
+            Scalar::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            Scalar::U16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            Scalar::I32 => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+            Scalar::U32 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+            Scalar::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+            Scalar::F64 => f64::from_le_bytes(bytes.try_into().unwrap()),
+        });
+    }
+
+    /// Reads a list length or a vertex index, which must be a non-negative integer
+    fn read_index(&mut self, ty: Scalar) -> Result<u32, PlyError> {
+        let value = self.read(ty)?;
+        if !(value >= 0.0 && value <= u32::MAX as f64 && value.fract() == 0.0) {
+            return Err(PlyError::Data(format!("invalid index {value}")));
+        }
+        return Ok(value as u32);
+    }
+
+    fn skip_list(&mut self, len: Scalar, item: Scalar) -> Result<(), PlyError> {
+        let len = self.read_index(len)?;
+        for _ in 0..len {
+            self.read(item)?;
+        }
+        return Ok(());
+    }
+
+    fn next_token(&mut self) -> Result<&str, PlyError> {
+        loop {
+            let rest = &self.line[self.position..];
+            let trimmed = rest.trim_start();
+            if !trimmed.is_empty() {
+                let start = self.position + rest.len() - trimmed.len();
+                let end = trimmed.find(char::is_whitespace).map_or(self.line.len(), |x| start + x);
+                self.position = end;
+                return Ok(&self.line[start..end]);
+            }
+
+            self.line.clear();
+            self.position = 0;
+            if self.reader.read_line(&mut self.line)? == 0 {
+                return Err(PlyError::Data("unexpected end of file".into()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, PlyError};
+    use crate::{
+        math::Vec3,
+        object::{Object, Ray},
+    };
+
+    const ASCII: &str = "ply
+format ascii 1.0
+comment unit quad with vertex colors
+element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+element face 1
+property list uchar int vertex_indices
+end_header
+0 0 0 255 0 0
+1 0 0 255 0 0
+1 1 0 0 0 255
+0 1 0 0 0 255
+4 0 1 2 3
+";
+
+    fn binary(little_endian: bool) -> Vec<u8> {
+        let format = match little_endian {
+            true => "binary_little_endian",
+            false => "binary_big_endian",
+        };
+
+        let mut result = ASCII[..ASCII.find("end_header").unwrap()]
+            .replace("ascii", format)
+            .into_bytes();
+        result.extend_from_slice(b"end_header\n");
+
+        let vertices = [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+        for (i, vertex) in vertices.into_iter().enumerate() {
+            for x in vertex {
+                result.extend(match little_endian {
+                    true => x.to_le_bytes(),
+                    false => x.to_be_bytes(),
+                });
+            }
+            result.extend(match i < 2 {
+                true => [255, 0, 0],
+                false => [0, 0, 255],
+            });
+        }
+
+        result.push(4);
+        for i in 0..4i32 {
+            result.extend(match little_endian {
+                true => i.to_le_bytes(),
+                false => i.to_be_bytes(),
+            });
+        }
+        return result;
+    }
+
+    #[test]
+    fn test_parse() {
+        let ray = Ray::new(Vec3::new(0.5, 0.25, 1.0), Vec3::new(0.0, 0.0, -1.0).unit());
+
+        for data in [ASCII.as_bytes().to_vec(), binary(true), binary(false)] {
+            let mesh = parse(data.as_slice()).unwrap();
+            assert_eq!(mesh.len(), 2);
+            assert_eq!(mesh.positions()[2], Vec3::new(1.0, 1.0, 0.0));
+
+            let hit = mesh.hit(ray, 0.0, f32::INFINITY).unwrap();
+            assert_eq!(hit.t, 1.0);
+            assert_eq!(hit.color, Some(Vec3::new(0.75, 0.0, 0.25)));
+        }
+    }
+
+    #[test]
+    fn test_invalid_index() {
+        // negative and fractional indices are rejected, instead of pointing at the first vertex
+        for face in ["4 0 1 -2 3", "4 0 1 2.5 3", "-1 0 1 2", "4 0 1 2 4294967296"] {
+            let data = ASCII.replace("4 0 1 2 3", face);
+            assert!(matches!(parse(data.as_bytes()), Err(PlyError::Data(_))), "{face}");
+        }
+
+        let mut data = binary(true);
+        let len = data.len();
+        data[len - 4..].copy_from_slice(&(-2i32).to_le_bytes());
+        assert!(matches!(parse(data.as_slice()), Err(PlyError::Data(_))));
+    }
+
+    #[test]
+    fn test_degenerate_normal() {
+        let data = "ply
+format ascii 1.0
+element vertex 3
+property float x
+property float y
+property float z
+property float nx
+property float ny
+property float nz
+element face 1
+property list uchar int vertex_indices
+end_header
+0 0 0 0 0 1
+1 0 0 0 0 0
+0 1 0 0 0 1
+3 0 1 2
+";
+
+        // the mesh falls back to its geometric normals, instead of interpolating a NaN
+        let mesh = parse(data.as_bytes()).unwrap();
+        assert!(mesh.normals().is_none());
+        let ray = Ray::new(Vec3::new(0.25, 0.25, 1.0), Vec3::new(0.0, 0.0, -1.0).unit());
+        assert_eq!(mesh.hit(ray, 0.0, f32::INFINITY).unwrap().normal, Vec3::new(0.0, 0.0, 1.0).unit());
+
+        let data = data.replace("1 0 0 0 0 0", "1 0 0 0 0 2");
+        assert!(parse(data.as_bytes()).unwrap().normals().is_some());
+    }
+}
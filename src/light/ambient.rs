@@ -1,5 +1,5 @@
 use crate::math::Vec3;
-use super::{DynLight, Light};
+use super::{DynLight, Light, LightSample};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Ambient {
@@ -20,7 +20,11 @@ impl Ambient {
 
 impl Light for Ambient {
     #[inline]
-    fn hits (&self, _point: Vec3) -> Option<Vec3> {
-        Some(self.color)
+    fn sample (&self, _point: Vec3) -> Option<LightSample> {
+        Some(LightSample {
+            color: self.color,
+            direction: None,
+            distance: f32::INFINITY
+        })
     }
 }
\ No newline at end of file
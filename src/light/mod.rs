@@ -1,31 +1,42 @@
 flat_mod! { point, ambient }
 
 use std::sync::Arc;
-use crate::{math::Vec3};
+use crate::{math::{Vec3, UnitVec3}};
 
 pub type DynLight<'a> = Box<dyn 'a + Light>;
 
+/// Contribution of a light towards a specific point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightSample {
+    pub color: Vec3,
+    /// Direction from the point towards the light, or `None` if the light
+    /// reaches the point from every direction (and thus can't be occluded)
+    pub direction: Option<UnitVec3>,
+    /// Distance from the point to the light
+    pub distance: f32,
+}
+
 pub trait Light: Send + Sync {
-    fn hits (&self, point: Vec3) -> Option<Vec3>;
+    fn sample (&self, point: Vec3) -> Option<LightSample>;
 }
 
 impl<T: ?Sized + Light> Light for &T {
     #[inline]
-    fn hits (&self, point: Vec3) -> Option<Vec3> {
-        T::hits(*self, point)
+    fn sample (&self, point: Vec3) -> Option<LightSample> {
+        T::sample(*self, point)
     }
 }
 
 impl<T: ?Sized + Light> Light for Box<T> {
     #[inline]
-    fn hits (&self, point: Vec3) -> Option<Vec3> {
-        T::hits(self, point)
+    fn sample (&self, point: Vec3) -> Option<LightSample> {
+        T::sample(self, point)
     }
 }
 
 impl<T: ?Sized + Light> Light for Arc<T> {
     #[inline]
-    fn hits (&self, point: Vec3) -> Option<Vec3> {
-        T::hits(self, point)
+    fn sample (&self, point: Vec3) -> Option<LightSample> {
+        T::sample(self, point)
     }
-}
\ No newline at end of file
+}
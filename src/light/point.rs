@@ -1,5 +1,5 @@
 use crate::{math::Vec3};
-use super::{Light, DynLight};
+use super::{Light, DynLight, LightSample};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Point {
@@ -22,10 +22,16 @@ impl Point {
 
 impl Light for Point {
     #[inline]
-    fn hits (&self, point: Vec3) -> Option<Vec3> {
-        let dist = point.distance(self.point);
+    fn sample (&self, point: Vec3) -> Option<LightSample> {
+        let delta = self.point - point;
+        let dist = delta.norm();
         let intensity = self.intensity / (dist * dist);
         if intensity <= f32::EPSILON { return None; }
-        return Some(intensity * self.color)
+
+        return Some(LightSample {
+            color: intensity * self.color,
+            direction: Some(delta.unit()),
+            distance: dist
+        })
     }
 }
\ No newline at end of file
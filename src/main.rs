@@ -6,13 +6,16 @@
     exit_status_error
 )]
 use light::{Point, Ambient};
+use std::sync::Arc;
 
 use crate::{
-    display::{Camera, Framebuffer},
+    display::{Camera, Filter, Framebuffer},
     element::Element,
+    material::{BlinnPhong, Conductor, Dielectric, Ggx, Principled},
     math::Vec3,
     object::sphere::Sphere,
     renderer::Renderer,
+    sampler::Sobol,
 };
 
 macro_rules! flat_mod {
@@ -24,33 +27,66 @@ macro_rules! flat_mod {
     };
 }
 
+pub mod bvh;
 pub mod display;
 pub mod element;
+pub mod format;
+pub mod import;
 pub mod light;
+pub mod material;
 pub mod math;
 pub mod object;
+pub mod random;
 pub mod renderer;
+pub mod sampler;
 
 fn main() -> anyhow::Result<()> {
-    let frame = Framebuffer::new(100, 100, Camera::default())?; // [120, 50]
-    let mut renderer = Renderer::new(
-        frame,
-        [
+    let frame = Framebuffer::new(100, 100, Camera::default())?
+        .with_sampler(Sobol::new(16, 0))
+        .with_filter(Filter::mitchell(2.0)); // [120, 50]
+
+    // render the given OBJ or PLY file, or the demo scene if there's none
+    let mut args = std::env::args_os().skip(1).map(std::path::PathBuf::from);
+    let path = args.next();
+    let output = args.next().unwrap_or_else(|| "result.png".into());
+    let elements = match path {
+        Some(path) if path.extension().map_or(false, |x| x.eq_ignore_ascii_case("ply")) => vec![
+            Element::new_unzise(import::ply::load(path)?, Arc::new(import::DEFAULT_MATERIAL)),
+        ],
+        Some(path) => import::obj::load(path)?,
+        None => vec![
             Element::new_unzise(
                 Sphere::new(Vec3::new(1.0, -1.0, -1.0), 0.5),
-                Vec3::new(1.0, 0.0, 0.0),
+                Arc::new(BlinnPhong::new(Vec3::new(1.0, 0.0, 0.0), Vec3::splat(0.5), 50.0)),
             ),
             Element::new_unzise(
                 Sphere::new(Vec3::new(1.0, 0.0, -2.0), 0.5),
-                Vec3::new(0.0, 1.0, 0.0),
+                Arc::new(Principled {
+                    clearcoat: 1.0,
+                    ..Principled::new(Vec3::new(0.0, 1.0, 0.0))
+                }),
+            ),
+            Element::new_unzise(
+                Sphere::new(Vec3::new(0.3, -0.3, -1.2), 0.25),
+                Arc::new(Dielectric::GLASS),
+            ),
+            Element::new_unzise(
+                Sphere::new(Vec3::new(-0.8, -0.6, -1.8), 0.4),
+                Arc::new(Conductor::GOLD.with_roughness(Ggx::from_roughness(0.3))),
             ),
         ],
+    };
+
+    let mut renderer = Renderer::new(
+        frame,
+        elements,
         [
             Point::new_unsize(Vec3::ZERO, Vec3::new(1., 1., 1.), 0.5),
             Ambient::new_unsize(0.1 * Vec3::new(1., 1., 1.))
         ],
     );
 
-    renderer.render(1)?;
+    let image = renderer.render(8);
+    format::save(output, &image, renderer.frame().display_transform)?;
     Ok(())
 }
@@ -0,0 +1,197 @@
+use super::{same_hemisphere, BsdfSample, Ggx, Material};
+use crate::{
+    math::{Vec2, Vec3},
+    object::Hit,
+};
+
+/// Metal, whose reflectance follows from its complex index of refraction `eta + i k`,
+/// roughened by a GGX distribution of microfacets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Conductor {
+    /// Real part of the index of refraction, for every channel
+    pub eta: Vec3,
+    /// Absorption coefficient (the imaginary part of the index of refraction), for every channel
+    pub k: Vec3,
+    pub roughness: Ggx,
+}
+
+impl Conductor {
+    // measured indices, sampled at 650, 550 and 450nm
+    pub const GOLD: Self = Self::new(Vec3::new(0.143, 0.374, 1.442), Vec3::new(3.983, 2.385, 1.603));
+    pub const COPPER: Self = Self::new(Vec3::new(0.200, 0.924, 1.102), Vec3::new(3.912, 2.452, 2.142));
+    pub const ALUMINIUM: Self = Self::new(Vec3::new(1.657, 0.880, 0.521), Vec3::new(9.224, 6.270, 4.837));
+    pub const SILVER: Self = Self::new(Vec3::new(0.155, 0.117, 0.138), Vec3::new(4.828, 3.122, 2.147));
+
+    /// Smooth conductor with the given complex index of refraction
+    #[inline]
+    pub const fn new(eta: Vec3, k: Vec3) -> Self {
+        return Self {
+            eta,
+            k,
+            roughness: Ggx::SMOOTH,
+        };
+    }
+
+    /// Smooth conductor with the given reflectance at normal incidence, and `edge_tint` shaping
+    /// the color of its reflections towards grazing angles
+    // "Artist Friendly Metallic Fresnel" (Gulbrandsen, 2014)
+    pub fn from_reflectance(reflectance: Vec3, edge_tint: Vec3) -> Self {
+        let mut eta = [0.0; 3];
+        let mut k = [0.0; 3];
+        for (i, (r, g)) in reflectance.to_array().into_iter().zip(edge_tint.to_array()).enumerate() {
+            let r = r.clamp(0.0, 0.999);
+            let g = g.clamp(0.0, 1.0);
+            let n = g * (1.0 - r) / (1.0 + r) + (1.0 - g) * (1.0 + r.sqrt()) / (1.0 - r.sqrt());
+            eta[i] = n;
+            k[i] = f32::sqrt(f32::max(0.0, (r * (n + 1.0).powi(2) - (n - 1.0).powi(2)) / (1.0 - r)));
+        }
+        return Self::new(Vec3::from_array(eta), Vec3::from_array(k));
+    }
+
+    #[inline]
+    pub const fn with_roughness(self, roughness: Ggx) -> Self {
+        return Self { roughness, ..self };
+    }
+
+    /// Fraction of the light reflected for every channel, with `cos_i` the cosine of the angle of incidence
+    #[inline]
+    pub fn fresnel(&self, cos_i: f32) -> Vec3 {
+        let [eta, k] = [self.eta.to_array(), self.k.to_array()];
+        return Vec3::new(
+            fresnel_conductor(cos_i, eta[0], k[0]),
+            fresnel_conductor(cos_i, eta[1], k[1]),
+            fresnel_conductor(cos_i, eta[2], k[2]),
+        );
+    }
+}
+
+impl Material for Conductor {
+    fn eval(&self, _: &Hit, wo: Vec3, wi: Vec3) -> Vec3 {
+        if self.roughness.is_smooth() || !same_hemisphere(wo, wi) {
+            return Vec3::ZERO;
+        }
+
+        let wm = wo + wi;
+        if wm == Vec3::ZERO {
+            return Vec3::ZERO;
+        }
+        let wm = wm.unit().to_vec();
+        let wm = wm.z().signum() * wm;
+
+        let (cos_o, cos_i) = (wo.z().abs(), wi.z().abs());
+        let d = self.roughness.d(wm);
+        let g = self.roughness.g(wo, wi);
+        return (d * g / (4.0 * cos_o * cos_i)) * self.fresnel((wo * wm).abs());
+    }
+
+    fn sample(&self, hit: &Hit, wo: Vec3, u: Vec2) -> Option<BsdfSample> {
+        if self.roughness.is_smooth() {
+            let wi = Vec3::new(-wo.x(), -wo.y(), wo.z());
+            let cos = wi.z().abs();
+            if cos == 0.0 {
+                return None;
+            }
+
+            return Some(BsdfSample {
+                wi,
+                value: self.fresnel(cos) / cos,
+                pdf: 1.0,
+                specular: true,
+            });
+        }
+
+        // the distribution's normals point up, so sample it from above
+        let flip = wo.z().signum();
+        let wm = flip * self.roughness.sample_visible(flip * wo, u);
+        let wi = 2.0 * (wo * wm) * wm - wo;
+        if !same_hemisphere(wo, wi) {
+            return None;
+        }
+
+        return Some(BsdfSample {
+            wi,
+            value: self.eval(hit, wo, wi),
+            pdf: self.pdf(hit, wo, wi),
+            specular: false,
+        });
+    }
+
+    fn pdf(&self, _: &Hit, wo: Vec3, wi: Vec3) -> f32 {
+        if self.roughness.is_smooth() || !same_hemisphere(wo, wi) {
+            return 0.0;
+        }
+
+        let wm = wo + wi;
+        if wm == Vec3::ZERO {
+            return 0.0;
+        }
+        let wm = wm.unit().to_vec();
+        let wm = wm.z().signum() * wm;
+
+        // reflecting around the normal halves the angles, and so shrinks the solid angle
+        return self.roughness.visible_d(wo, wm) / (4.0 * (wo * wm).abs());
+    }
+}
+
+/// Fraction of unpolarized light reflected by a conductor with the index of refraction `eta + i k`,
+/// with `cos_i` the cosine of the angle of incidence
+// https://seblagarde.wordpress.com/2013/04/29/memo-on-fresnel-equations/
+#[inline]
+pub fn fresnel_conductor(cos_i: f32, eta: f32, k: f32) -> f32 {
+    let cos2 = cos_i.clamp(0.0, 1.0).powi(2);
+    let sin2 = 1.0 - cos2;
+
+    let t0 = eta * eta - k * k - sin2;
+    let a2_plus_b2 = f32::sqrt(t0 * t0 + 4.0 * eta * eta * k * k);
+    let a = f32::sqrt(f32::max(0.0, (a2_plus_b2 + t0) / 2.0));
+
+    let t1 = a2_plus_b2 + cos2;
+    let t2 = 2.0 * a * cos_i.clamp(0.0, 1.0);
+    let perpendicular = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2_plus_b2 + sin2 * sin2;
+    let t4 = t2 * sin2;
+    let parallel = perpendicular * (t3 - t4) / (t3 + t4);
+
+    return (perpendicular + parallel) / 2.0;
+}
+
+#[cfg(test)]
+#[test]
+fn test_conductor() {
+    use super::{
+        fresnel_dielectric,
+        tests::{check_material, hit},
+    };
+
+    // ((eta - 1)² + k²) / ((eta + 1)² + k²) at normal incidence
+    let expected = (0.143f32 - 1.0).powi(2) + 3.983f32.powi(2);
+    let expected = expected / ((0.143f32 + 1.0).powi(2) + 3.983f32.powi(2));
+    assert!((fresnel_conductor(1.0, 0.143, 3.983) - expected).abs() < 1e-5);
+    // without absorption, it's a dielectric
+    for cos in [1.0, 0.7, 0.2] {
+        assert!((fresnel_conductor(cos, 1.5, 0.0) - fresnel_dielectric(cos, 1.0 / 1.5)).abs() < 1e-5);
+    }
+    assert!((fresnel_conductor(0.0, 0.2, 3.9) - 1.0).abs() < 1e-5);
+
+    let reflectance = Vec3::new(0.9, 0.6, 0.1);
+    for edge_tint in [Vec3::splat(1.0), Vec3::new(0.5, 0.5, 0.5)] {
+        let metal = Conductor::from_reflectance(reflectance, edge_tint);
+        assert!((metal.fresnel(1.0) - reflectance).norm() < 1e-4, "{:?}", metal.fresnel(1.0));
+    }
+
+    // gold reflects more red than blue
+    let gold = Conductor::GOLD.fresnel(1.0);
+    assert!(gold.x() > gold.z());
+
+    for metal in [Conductor::GOLD, Conductor::COPPER, Conductor::ALUMINIUM, Conductor::SILVER] {
+        let wo = Vec3::new(0.6, 0.0, 0.8);
+        check_material(&metal, wo);
+        check_material(&metal.with_roughness(Ggx::from_roughness(0.4)), wo);
+        check_material(&metal.with_roughness(Ggx::new(0.2, 0.7)), Vec3::new(0.0, 0.28, 0.96));
+    }
+
+    let sample = Conductor::SILVER.sample(&hit(), Vec3::new(0.0, 0.0, 1.0), Vec2::ZERO).unwrap();
+    assert!(sample.specular && sample.wi == Vec3::new(0.0, 0.0, 1.0));
+    assert!((sample.weight() - Conductor::SILVER.fresnel(1.0)).norm() < 1e-6);
+}
@@ -0,0 +1,259 @@
+use super::{same_hemisphere, BsdfSample, Ggx, Material};
+use crate::{
+    math::{Vec2, Vec3},
+    object::{Hit, Ray},
+};
+
+/// Boundary between two transparent media (i.e. glass or water, surrounded by air), which is
+/// smooth unless roughened by a GGX distribution of microfacets.
+///
+/// The index of refraction is the one inside the object, relative to the one outside of it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dielectric {
+    pub ior: f32,
+    /// Color multiplying the transmitted light
+    pub tint: Vec3,
+    pub roughness: Ggx,
+}
+
+impl Dielectric {
+    pub const GLASS: Self = Self::new(1.5);
+    pub const WATER: Self = Self::new(1.333);
+    pub const DIAMOND: Self = Self::new(2.42);
+
+    #[inline]
+    pub const fn new(ior: f32) -> Self {
+        return Self {
+            ior,
+            tint: Vec3::splat(1.0),
+            roughness: Ggx::SMOOTH,
+        };
+    }
+
+    #[inline]
+    pub const fn with_tint(self, tint: Vec3) -> Self {
+        return Self { tint, ..self };
+    }
+
+    #[inline]
+    pub const fn with_roughness(self, roughness: Ggx) -> Self {
+        return Self { roughness, ..self };
+    }
+
+    /// Ratio between the indices of refraction of the side the ray comes from, and of the other side
+    #[inline]
+    fn eta(&self, hit: &Hit) -> f32 {
+        return match hit.front_face {
+            true => self.ior.recip(),
+            false => self.ior,
+        };
+    }
+
+    /// Ratio between the indices of refraction of the side of `wo` and of the other side
+    #[inline]
+    fn side_eta(&self, hit: &Hit, wo: Vec3) -> f32 {
+        return match wo.z() > 0.0 {
+            true => self.eta(hit),
+            false => self.eta(hit).recip(),
+        };
+    }
+
+    /// Ratio between the indices of refraction of the sides of `wo` and `wi`, and the generalized
+    /// half vector between them (the microfacet normal that scatters one into the other), facing `+z`.
+    /// Returns `None` if no microfacet can scatter `wo` into `wi`.
+    fn half_vector(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> Option<(f32, Vec3)> {
+        let eta = match wo.z() * wi.z() {
+            cos if cos > 0.0 => 1.0,
+            cos if cos < 0.0 => self.side_eta(hit, wo),
+            _ => return None,
+        };
+
+        let wm = eta * wo + wi;
+        if wm == Vec3::ZERO {
+            return None;
+        }
+        let wm = wm.unit().to_vec();
+        let wm = wm.z().signum() * wm;
+
+        // light can't reach the back of a microfacet
+        if (wm * wi) * wi.z() <= 0.0 || (wm * wo) * wo.z() <= 0.0 {
+            return None;
+        }
+        return Some((eta, wm));
+    }
+
+    /// Probability of sampling reflection instead of refraction, which is the reflectance
+    /// of the macroscopic surface, kept away from 0 and 1 so both lobes can be sampled
+    #[inline]
+    fn reflect_probability(&self, hit: &Hit, wo: Vec3) -> f32 {
+        return fresnel_dielectric(wo.z().abs(), self.side_eta(hit, wo)).clamp(0.05, 0.95);
+    }
+
+    /// Reflects or refracts `wo` through a microfacet visible from it
+    fn sample_rough(&self, hit: &Hit, wo: Vec3, u: Vec2) -> Option<BsdfSample> {
+        let p = self.reflect_probability(hit, wo);
+        let (reflect, u) = match u.x() < p {
+            true => (true, Vec2::new(u.x() / p, u.y())),
+            false => (false, Vec2::new(f32::min((u.x() - p) / (1.0 - p), 1.0 - f32::EPSILON), u.y())),
+        };
+
+        // the distribution's normals point up, so sample it from above
+        let flip = wo.z().signum();
+        let wm = flip * self.roughness.sample_visible(flip * wo, u);
+        let wi = match reflect {
+            true => 2.0 * (wo * wm) * wm - wo,
+            false => Ray::new(Vec3::ZERO, (-wo).unit()).refract(wm.unit(), self.side_eta(hit, wo))?.to_vec(),
+        };
+
+        let pdf = self.pdf(hit, wo, wi);
+        if !(pdf > 0.0) {
+            return None;
+        }
+        return Some(BsdfSample {
+            wi,
+            value: self.eval(hit, wo, wi),
+            pdf,
+            specular: false,
+        });
+    }
+}
+
+impl Material for Dielectric {
+    fn eval(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> Vec3 {
+        if self.roughness.is_smooth() {
+            return Vec3::ZERO;
+        }
+        let Some((eta, wm)) = self.half_vector(hit, wo, wi) else { return Vec3::ZERO };
+
+        let (cos_o, cos_i) = (wo.z().abs(), wi.z().abs());
+        let d = self.roughness.d(wm);
+        let g = self.roughness.g(wo, wi);
+        let reflectance = fresnel_dielectric((wo * wm).abs(), self.side_eta(hit, wo));
+
+        if same_hemisphere(wo, wi) {
+            return Vec3::splat(d * g * reflectance / (4.0 * cos_o * cos_i));
+        }
+
+        // the solid angle of the microfacet normals shrinks or stretches through refraction
+        let denom = (wi * wm + eta * (wo * wm)).powi(2);
+        let transmittance = (1.0 - reflectance) * d * g * ((wi * wm) * (wo * wm)).abs() / (cos_o * cos_i * denom);
+        return (transmittance * eta * eta) * self.tint;
+    }
+
+    fn sample(&self, hit: &Hit, wo: Vec3, u: Vec2) -> Option<BsdfSample> {
+        if !self.roughness.is_smooth() {
+            return self.sample_rough(hit, wo, u);
+        }
+
+        let eta = self.eta(hit);
+        let cos_i = wo.z().abs();
+        let reflectance = fresnel_dielectric(cos_i, eta);
+
+        // pick reflection or refraction in proportion to the light they carry
+        if u.x() < reflectance {
+            let wi = Vec3::new(-wo.x(), -wo.y(), wo.z());
+            return Some(BsdfSample {
+                wi,
+                value: Vec3::splat(reflectance / cos_i),
+                pdf: reflectance,
+                specular: true,
+            });
+        }
+
+        let normal = Vec3::new(0.0, 0.0, wo.z().signum()).unit();
+        let wi = Ray::new(Vec3::ZERO, (-wo).unit()).refract(normal, eta)?.to_vec();
+
+        // radiance is compressed into a smaller solid angle when entering a denser medium
+        let transmittance = (1.0 - reflectance) * eta * eta;
+        return Some(BsdfSample {
+            wi,
+            value: (transmittance / wi.z().abs()) * self.tint,
+            pdf: 1.0 - reflectance,
+            specular: true,
+        });
+    }
+
+    fn pdf(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> f32 {
+        if self.roughness.is_smooth() {
+            return 0.0;
+        }
+        let Some((eta, wm)) = self.half_vector(hit, wo, wi) else { return 0.0 };
+
+        let p = self.reflect_probability(hit, wo);
+        let pdf = self.roughness.visible_d(wo, wm);
+        if same_hemisphere(wo, wi) {
+            return p * pdf / (4.0 * (wo * wm).abs());
+        }
+
+        let denom = (wi * wm + eta * (wo * wm)).powi(2);
+        return (1.0 - p) * pdf * (wi * wm).abs() / denom;
+    }
+}
+
+/// Fraction of unpolarized light reflected by a smooth dielectric boundary, with `cos_i` the cosine of
+/// the angle of incidence and `eta` the ratio of the indices of refraction of the incident and transmitted sides.
+/// All of the light is reflected past the critical angle.
+#[inline]
+pub fn fresnel_dielectric(cos_i: f32, eta: f32) -> f32 {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let sin2_t = eta * eta * (1.0 - cos_i * cos_i);
+    if sin2_t >= 1.0 {
+        return 1.0;
+    }
+
+    let cos_t = f32::sqrt(1.0 - sin2_t);
+    let parallel = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    let perpendicular = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    return (parallel * parallel + perpendicular * perpendicular) / 2.0;
+}
+
+#[cfg(test)]
+#[test]
+fn test_dielectric() {
+    use super::tests::{check_material, hit};
+
+    // ((n1 - n2) / (n1 + n2))² at normal incidence, the same from both sides
+    assert!((fresnel_dielectric(1.0, 1.0 / 1.5) - 0.04).abs() < 1e-6);
+    assert!((fresnel_dielectric(1.0, 1.5) - 0.04).abs() < 1e-6);
+    // grazing angles reflect everything
+    assert!(fresnel_dielectric(1e-4, 1.0 / 1.5) > 0.99);
+    // total internal reflection, past the critical angle of glass (~41.8°)
+    assert_eq!(fresnel_dielectric(f32::cos(45f32.to_radians()), 1.5), 1.0);
+    assert!(fresnel_dielectric(f32::cos(40f32.to_radians()), 1.5) < 1.0);
+
+    let glass = Dielectric::GLASS;
+    let wo = Vec3::new(0.6, 0.0, 0.8);
+    check_material(&glass, wo);
+
+    // entering: refraction bends towards the normal, with sin(t) = sin(i) / 1.5
+    let outside = hit();
+    let sample = glass.sample(&outside, wo, Vec2::new(0.5, 0.0)).unwrap();
+    assert!(sample.specular && sample.wi.z() < 0.0);
+    assert!((sample.wi.x() + 0.6 / 1.5).abs() < 1e-5);
+
+    // leaving at a grazing angle: total internal reflection
+    let inside = Hit {
+        front_face: false,
+        ..outside
+    };
+    let wo = Vec3::new(0.8, 0.0, 0.6);
+    for u in [0.0, 0.5, 0.999] {
+        let sample = glass.sample(&inside, wo, Vec2::new(u, 0.0)).unwrap();
+        assert_eq!(sample.wi, Vec3::new(-0.8, 0.0, 0.6));
+        assert!((sample.weight() - Vec3::splat(1.0)).norm() < 1e-5);
+    }
+
+    // rough glass scatters around the smooth directions, reflecting and refracting
+    let rough = glass.with_roughness(Ggx::from_roughness(0.3));
+    for wo in [Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.6, 0.0, 0.8), Vec3::new(0.0, 0.96, 0.28)] {
+        check_material(&rough, wo);
+    }
+    check_material(&glass.with_roughness(Ggx::new(0.1, 0.5)), Vec3::new(0.6, 0.0, 0.8));
+
+    let wo = Vec3::new(0.6, 0.0, 0.8);
+    let refracted = Vec3::new(-0.4, 0.0, -f32::sqrt(1.0 - 0.16));
+    let off = Vec3::new(0.4, 0.0, -f32::sqrt(1.0 - 0.16));
+    assert!(rough.eval(&outside, wo, refracted).x() > rough.eval(&outside, wo, off).x());
+    assert!(rough.eval(&outside, wo, Vec3::new(-0.6, 0.0, 0.8)).x() > 0.0);
+    assert!(rough.pdf(&outside, wo, refracted) > 0.0);
+}
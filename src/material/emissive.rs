@@ -0,0 +1,68 @@
+use super::{BsdfSample, Material};
+use crate::{
+    math::{Vec2, Vec3},
+    object::Hit,
+};
+
+/// Any material, glowing with a constant radiance on top of the light it scatters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Emissive<M> {
+    pub material: M,
+    /// Radiance emitted towards every direction
+    pub radiance: Vec3,
+}
+
+impl<M> Emissive<M> {
+    #[inline]
+    pub const fn new(material: M, radiance: Vec3) -> Self {
+        return Self { material, radiance };
+    }
+}
+
+impl<M: Material> Material for Emissive<M> {
+    #[inline]
+    fn eval(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> Vec3 {
+        return self.material.eval(hit, wo, wi);
+    }
+
+    #[inline]
+    fn sample(&self, hit: &Hit, wo: Vec3, u: Vec2) -> Option<BsdfSample> {
+        return self.material.sample(hit, wo, u);
+    }
+
+    #[inline]
+    fn pdf(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> f32 {
+        return self.material.pdf(hit, wo, wi);
+    }
+
+    #[inline]
+    fn albedo(&self, hit: &Hit, wo: Vec3) -> Vec3 {
+        return self.material.albedo(hit, wo);
+    }
+
+    #[inline]
+    fn emitted(&self, hit: &Hit, wo: Vec3) -> Vec3 {
+        return self.radiance + self.material.emitted(hit, wo);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_emissive() {
+    use super::{tests::hit, Lambertian, Principled};
+
+    let hit = hit();
+    let (wo, wi) = (Vec3::new(0.6, 0.0, 0.8), Vec3::new(0.0, 0.0, 1.0));
+    let lambertian = Lambertian::new(Vec3::new(0.2, 0.5, 0.9));
+    let material = Emissive::new(lambertian, Vec3::splat(3.0));
+    assert_eq!(material.eval(&hit, wo, wi), lambertian.eval(&hit, wo, wi));
+    assert_eq!(material.albedo(&hit, wo), lambertian.albedo);
+    assert_eq!(material.emitted(&hit, wo), Vec3::splat(3.0));
+
+    // emission adds up with the material's own
+    let glowing = Principled {
+        emission: Vec3::splat(1.0),
+        ..Principled::default()
+    };
+    assert_eq!(Emissive::new(glowing, Vec3::splat(3.0)).emitted(&hit, wo), Vec3::splat(4.0));
+}
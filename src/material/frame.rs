@@ -0,0 +1,36 @@
+use crate::{
+    math::{UnitVec3, Vec3},
+    object::Hit,
+};
+
+/// Orthonormal basis around the shading normal of a hit, which is the `z` axis of
+/// the local space materials work in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadingFrame {
+    pub tangent: UnitVec3,
+    pub bitangent: UnitVec3,
+    pub normal: UnitVec3,
+}
+
+impl ShadingFrame {
+    #[inline]
+    pub fn new(hit: &Hit) -> Self {
+        return Self {
+            tangent: hit.tangent,
+            bitangent: hit.bitangent,
+            normal: hit.normal,
+        };
+    }
+
+    /// Expresses a world-space vector in the local frame
+    #[inline]
+    pub fn to_local(&self, v: Vec3) -> Vec3 {
+        return Vec3::new(self.tangent * v, self.bitangent * v, self.normal * v);
+    }
+
+    /// Expresses a local vector in world space
+    #[inline]
+    pub fn to_world(&self, v: Vec3) -> Vec3 {
+        return self.tangent * v.x() + self.bitangent * v.y() + self.normal * v.z();
+    }
+}
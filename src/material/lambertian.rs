@@ -0,0 +1,78 @@
+use super::{same_hemisphere, sample_cosine_hemisphere, BsdfSample, Material};
+use crate::{
+    math::{Vec2, Vec3},
+    object::Hit,
+};
+use std::f32::consts::FRAC_1_PI;
+
+/// Ideal diffuse surface, scattering light equally in every direction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lambertian {
+    /// Fraction of the light that's reflected, for every channel.
+    /// It's tinted by the vertex colors of the objects that have them.
+    pub albedo: Vec3,
+}
+
+impl Lambertian {
+    #[inline]
+    pub const fn new(albedo: Vec3) -> Self {
+        return Self { albedo };
+    }
+
+    #[inline]
+    fn color(&self, hit: &Hit) -> Vec3 {
+        return hit.color.map_or(self.albedo, |color| self.albedo.wide_mul(color));
+    }
+}
+
+impl Material for Lambertian {
+    #[inline]
+    fn eval(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> Vec3 {
+        if !same_hemisphere(wo, wi) {
+            return Vec3::ZERO;
+        }
+        return FRAC_1_PI * self.color(hit);
+    }
+
+    #[inline]
+    fn sample(&self, hit: &Hit, wo: Vec3, u: Vec2) -> Option<BsdfSample> {
+        let mut wi = sample_cosine_hemisphere(u);
+        if wo.z() < 0.0 {
+            wi.set_z(-wi.z());
+        }
+
+        return Some(BsdfSample {
+            wi,
+            value: FRAC_1_PI * self.color(hit),
+            pdf: wi.z().abs() * FRAC_1_PI,
+            specular: false,
+        });
+    }
+
+    #[inline]
+    fn pdf(&self, _: &Hit, wo: Vec3, wi: Vec3) -> f32 {
+        if !same_hemisphere(wo, wi) {
+            return 0.0;
+        }
+        return wi.z().abs() * FRAC_1_PI;
+    }
+
+    #[inline]
+    fn albedo(&self, hit: &Hit, _: Vec3) -> Vec3 {
+        return self.color(hit);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_lambertian() {
+    use super::tests::{check_material, hit};
+
+    let material = Lambertian::new(Vec3::new(0.2, 0.5, 0.9));
+    let wo = Vec3::new(0.6, 0.0, 0.8);
+    check_material(&material, wo);
+
+    assert_eq!(material.eval(&hit(), wo, Vec3::new(0.0, 0.0, -1.0)), Vec3::ZERO);
+    let sample = material.sample(&hit(), wo, Vec2::new(0.3, 0.7)).unwrap();
+    assert!((sample.weight() - material.albedo).norm() < 1e-5);
+}
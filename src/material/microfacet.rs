@@ -0,0 +1,166 @@
+use crate::math::{Vec2, Vec3};
+use std::f32::consts::PI;
+
+/// GGX (Trowbridge-Reitz) distribution of microfacet normals, with Smith's height-correlated shadowing.
+///
+/// Surfaces are modeled as tiny mirrors whose normals spread around the shading normal
+/// by `alpha_x` along the tangent, and by `alpha_y` along the bitangent.
+// "Understanding the Masking-Shadowing Function in Microfacet-Based BRDFs" (Heitz, 2014)
+// "Sampling the GGX Distribution of Visible Normals" (Heitz, 2018)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ggx {
+    pub alpha_x: f32,
+    pub alpha_y: f32,
+}
+
+impl Ggx {
+    /// Perfectly smooth surface, which only reflects (or refracts) in a single direction
+    pub const SMOOTH: Self = Self::new(0.0, 0.0);
+
+    /// Below this roughness, surfaces are treated as smooth, since the distribution
+    /// degenerates into a Dirac delta
+    const SMOOTH_ALPHA: f32 = 1e-3;
+
+    #[inline]
+    pub const fn new(alpha_x: f32, alpha_y: f32) -> Self {
+        return Self { alpha_x, alpha_y };
+    }
+
+    /// Distribution for the given perceptual roughness in `[0, 1]`, whose square is the
+    /// distribution's width, so that roughness changes look roughly linear
+    #[inline]
+    pub fn from_roughness(roughness: f32) -> Self {
+        let alpha = roughness.clamp(0.0, 1.0).powi(2);
+        return Self::new(alpha, alpha);
+    }
+
+    /// Anisotropic distribution for the given perceptual roughness, stretched along the tangent
+    /// as `anisotropy` goes from 0 to 1
+    // "Physically-Based Shading at Disney" (Burley, 2012)
+    #[inline]
+    pub fn from_anisotropic_roughness(roughness: f32, anisotropy: f32) -> Self {
+        let alpha = roughness.clamp(0.0, 1.0).powi(2);
+        let aspect = f32::sqrt(1.0 - 0.9 * anisotropy.clamp(0.0, 1.0));
+        return Self::new(alpha / aspect, alpha * aspect);
+    }
+
+    #[inline]
+    pub fn is_smooth(&self) -> bool {
+        return self.alpha_x.max(self.alpha_y) < Self::SMOOTH_ALPHA;
+    }
+
+    /// Density of microfacets with normal `wm`, per unit of solid angle and projected area
+    #[inline]
+    pub fn d(&self, wm: Vec3) -> f32 {
+        if wm.z() <= 0.0 {
+            return 0.0;
+        }
+
+        let (x, y) = (wm.x() / self.alpha_x, wm.y() / self.alpha_y);
+        let t = x * x + y * y + wm.z() * wm.z();
+        return 1.0 / (PI * self.alpha_x * self.alpha_y * t * t);
+    }
+
+    /// Smith's auxiliary function, which measures the microfacets' area hidden from `w`
+    #[inline]
+    pub fn lambda(&self, w: Vec3) -> f32 {
+        let (x, y) = (self.alpha_x * w.x(), self.alpha_y * w.y());
+        let tan2 = (x * x + y * y) / (w.z() * w.z());
+        if !tan2.is_finite() {
+            return f32::INFINITY;
+        }
+        return (f32::sqrt(1.0 + tan2) - 1.0) / 2.0;
+    }
+
+    /// Fraction of the microfacets visible from `w`
+    #[inline]
+    pub fn g1(&self, w: Vec3) -> f32 {
+        return 1.0 / (1.0 + self.lambda(w));
+    }
+
+    /// Fraction of the microfacets visible from both `wo` and `wi`
+    #[inline]
+    pub fn g(&self, wo: Vec3, wi: Vec3) -> f32 {
+        return 1.0 / (1.0 + self.lambda(wo) + self.lambda(wi));
+    }
+
+    /// Density of the normals visible from `w`
+    #[inline]
+    pub fn visible_d(&self, w: Vec3, wm: Vec3) -> f32 {
+        return self.g1(w) / w.z().abs() * self.d(wm) * (w * wm).abs();
+    }
+
+    /// Samples a microfacet normal visible from `w`, with a density of [`Ggx::visible_d`]
+    pub fn sample_visible(&self, w: Vec3, u: Vec2) -> Vec3 {
+        // stretch the distribution into a hemisphere, where visible normals are easy to sample
+        let wh = Vec3::new(self.alpha_x * w.x(), self.alpha_y * w.y(), w.z()).unit().to_vec();
+        let wh = match wh.z() < 0.0 {
+            true => -wh,
+            false => wh,
+        };
+
+        let t1 = match wh.z() < 0.99999 {
+            true => Vec3::new(0.0, 0.0, 1.0).cross(wh).unit().to_vec(),
+            false => Vec3::new(1.0, 0.0, 0.0),
+        };
+        let t2 = wh.cross(t1);
+
+        // uniform point on the disk, warped towards the part of the hemisphere facing `w`
+        let r = u.x().sqrt();
+        let phi = 2.0 * PI * u.y();
+        let (p1, p2) = (r * phi.cos(), r * phi.sin());
+        let h = f32::sqrt(1.0 - p1 * p1);
+        let s = (1.0 + wh.z()) / 2.0;
+        let p2 = (1.0 - s) * h + s * p2;
+        let pz = f32::sqrt(f32::max(0.0, 1.0 - p1 * p1 - p2 * p2));
+        let nh = p1 * t1 + p2 * t2 + pz * wh;
+
+        // and back to the ellipsoid
+        let wm = Vec3::new(self.alpha_x * nh.x(), self.alpha_y * nh.y(), f32::max(1e-6, nh.z()));
+        return wm.unit().to_vec();
+    }
+}
+
+impl Default for Ggx {
+    #[inline]
+    fn default() -> Self {
+        return Self::SMOOTH;
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_ggx() {
+    use crate::random::Pcg32;
+
+    let ggx = Ggx::new(0.3, 0.6);
+    let mut rng = Pcg32::new(7, 0);
+
+    // the projected area of the microfacets is the one of the surface
+    const N: usize = 200;
+    let mut area = 0.0;
+    for i in 0..N {
+        for j in 0..N {
+            let (cos, phi) = ((i as f32 + 0.5) / N as f32, 2.0 * PI * (j as f32 + 0.5) / N as f32);
+            let sin = f32::sqrt(1.0 - cos * cos);
+            let wm = Vec3::new(sin * phi.cos(), sin * phi.sin(), cos);
+            area += ggx.d(wm) * cos * (2.0 * PI / (N * N) as f32);
+        }
+    }
+    assert!((area - 1.0).abs() < 0.01, "{area}");
+
+    // visible normals face the viewer
+    let w = Vec3::new(0.6, 0.3, 0.5).unit().to_vec();
+    for _ in 0..1000 {
+        let wm = ggx.sample_visible(w, rng.next_vec2());
+        assert!(wm.z() > 0.0 && w * wm >= -1e-4, "{wm:?}");
+    }
+
+    assert_eq!(ggx.g1(Vec3::new(0.0, 0.0, 1.0)), 1.0);
+    assert!(ggx.g1(Vec3::new(0.99, 0.0, 0.14)) < ggx.g1(w));
+    assert!(ggx.g(w, w) <= ggx.g1(w));
+    assert!(Ggx::from_roughness(0.01).is_smooth() && !Ggx::from_roughness(0.1).is_smooth());
+
+    let anisotropic = Ggx::from_anisotropic_roughness(0.5, 0.8);
+    assert!(anisotropic.alpha_x > anisotropic.alpha_y);
+}
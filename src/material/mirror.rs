@@ -0,0 +1,62 @@
+use super::{BsdfSample, Material};
+use crate::{
+    math::{Vec2, Vec3},
+    object::Hit,
+};
+
+/// Perfectly smooth reflector
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mirror {
+    /// Fraction of the light that's reflected, for every channel
+    pub reflectance: Vec3,
+}
+
+impl Mirror {
+    #[inline]
+    pub const fn new(reflectance: Vec3) -> Self {
+        return Self { reflectance };
+    }
+}
+
+impl Material for Mirror {
+    #[inline]
+    fn eval(&self, _: &Hit, _: Vec3, _: Vec3) -> Vec3 {
+        return Vec3::ZERO;
+    }
+
+    #[inline]
+    fn sample(&self, _: &Hit, wo: Vec3, _: Vec2) -> Option<BsdfSample> {
+        // reflection around the normal, which is the z axis
+        let wi = Vec3::new(-wo.x(), -wo.y(), wo.z());
+        if wi.z() == 0.0 {
+            return None;
+        }
+
+        return Some(BsdfSample {
+            wi,
+            value: self.reflectance / wi.z().abs(),
+            pdf: 1.0,
+            specular: true,
+        });
+    }
+
+    #[inline]
+    fn pdf(&self, _: &Hit, _: Vec3, _: Vec3) -> f32 {
+        return 0.0;
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_mirror() {
+    use super::tests::{check_material, hit};
+
+    let material = Mirror::new(Vec3::splat(0.9));
+    let wo = Vec3::new(0.6, 0.0, 0.8);
+    check_material(&material, wo);
+
+    let sample = material.sample(&hit(), wo, Vec2::ZERO).unwrap();
+    assert!(sample.specular);
+    assert_eq!(sample.wi, Vec3::new(-0.6, 0.0, 0.8));
+    assert!((sample.weight() - Vec3::splat(0.9)).norm() < 1e-6);
+}
@@ -0,0 +1,214 @@
+flat_mod! { frame, conductor, dielectric, emissive, lambertian, microfacet, mirror, phong, principled }
+
+use crate::{
+    math::{Vec2, Vec3},
+    object::Hit,
+};
+use std::{f32::consts::PI, sync::Arc};
+
+pub type DynMaterial = Arc<dyn Material>;
+
+/// Direction sampled from a BSDF
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BsdfSample {
+    /// Direction the light comes from, in the local shading frame
+    pub wi: Vec3,
+    /// Value of the BSDF for the sampled direction
+    pub value: Vec3,
+    /// Probability density of sampling `wi`. For specular lobes, it's the probability of
+    /// picking the lobe instead.
+    pub pdf: f32,
+    /// `true` if the direction comes from a specular lobe, which [`Material::eval`] and
+    /// [`Material::pdf`] can't return (their value is a Dirac delta)
+    pub specular: bool,
+}
+
+impl BsdfSample {
+    /// Fraction of the light coming from `wi` that's scattered towards the outgoing direction,
+    /// accounting for the probability of sampling it
+    #[inline]
+    pub fn weight(&self) -> Vec3 {
+        return self.value * (self.wi.z().abs() / self.pdf);
+    }
+}
+
+/// Describes how a surface scatters light, through its bidirectional scattering distribution function.
+///
+/// Directions are normalized and given in the local shading frame of the hit (see [`ShadingFrame`]),
+/// where the shading normal is `+z` and always faces the side the ray came from. `wo` points
+/// towards the viewer, and `wi` towards the light, so both lie above the surface when light is reflected.
+pub trait Material: Send + Sync {
+    /// Value of the BSDF for light coming from `wi` and scattered towards `wo`.
+    /// Specular lobes aren't included.
+    fn eval(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> Vec3;
+
+    /// Samples the direction light comes from, given a uniformly distributed point `u` in `[0, 1)²`
+    fn sample(&self, hit: &Hit, wo: Vec3, u: Vec2) -> Option<BsdfSample>;
+
+    /// Probability density of [`Material::sample`] returning `wi`, with specular lobes excluded
+    fn pdf(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> f32;
+
+    /// Fraction of the light arriving from every direction that's reflected towards `wo`,
+    /// used for ambient lighting. Defaults to the albedo of a Lambertian surface with the same
+    /// value of the BSDF along the normal.
+    #[inline]
+    fn albedo(&self, hit: &Hit, wo: Vec3) -> Vec3 {
+        return PI * self.eval(hit, wo, Vec3::new(0.0, 0.0, 1.0));
+    }
+
+    /// Radiance emitted by the surface towards `wo`
+    #[inline]
+    fn emitted(&self, hit: &Hit, wo: Vec3) -> Vec3 {
+        let _ = (hit, wo);
+        return Vec3::ZERO;
+    }
+}
+
+impl<T: ?Sized + Material> Material for &T {
+    #[inline]
+    fn eval(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> Vec3 {
+        T::eval(*self, hit, wo, wi)
+    }
+
+    #[inline]
+    fn sample(&self, hit: &Hit, wo: Vec3, u: Vec2) -> Option<BsdfSample> {
+        T::sample(*self, hit, wo, u)
+    }
+
+    #[inline]
+    fn pdf(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> f32 {
+        T::pdf(*self, hit, wo, wi)
+    }
+
+    #[inline]
+    fn albedo(&self, hit: &Hit, wo: Vec3) -> Vec3 {
+        T::albedo(*self, hit, wo)
+    }
+
+    #[inline]
+    fn emitted(&self, hit: &Hit, wo: Vec3) -> Vec3 {
+        T::emitted(*self, hit, wo)
+    }
+}
+
+impl<T: ?Sized + Material> Material for Box<T> {
+    #[inline]
+    fn eval(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> Vec3 {
+        T::eval(self, hit, wo, wi)
+    }
+
+    #[inline]
+    fn sample(&self, hit: &Hit, wo: Vec3, u: Vec2) -> Option<BsdfSample> {
+        T::sample(self, hit, wo, u)
+    }
+
+    #[inline]
+    fn pdf(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> f32 {
+        T::pdf(self, hit, wo, wi)
+    }
+
+    #[inline]
+    fn albedo(&self, hit: &Hit, wo: Vec3) -> Vec3 {
+        T::albedo(self, hit, wo)
+    }
+
+    #[inline]
+    fn emitted(&self, hit: &Hit, wo: Vec3) -> Vec3 {
+        T::emitted(self, hit, wo)
+    }
+}
+
+impl<T: ?Sized + Material> Material for Arc<T> {
+    #[inline]
+    fn eval(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> Vec3 {
+        T::eval(self, hit, wo, wi)
+    }
+
+    #[inline]
+    fn sample(&self, hit: &Hit, wo: Vec3, u: Vec2) -> Option<BsdfSample> {
+        T::sample(self, hit, wo, u)
+    }
+
+    #[inline]
+    fn pdf(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> f32 {
+        T::pdf(self, hit, wo, wi)
+    }
+
+    #[inline]
+    fn albedo(&self, hit: &Hit, wo: Vec3) -> Vec3 {
+        T::albedo(self, hit, wo)
+    }
+
+    #[inline]
+    fn emitted(&self, hit: &Hit, wo: Vec3) -> Vec3 {
+        T::emitted(self, hit, wo)
+    }
+}
+
+/// `true` if both directions are on the same side of the surface
+#[inline]
+pub fn same_hemisphere(a: Vec3, b: Vec3) -> bool {
+    return a.z() * b.z() > 0.0;
+}
+
+/// Cosine-weighted direction on the upper hemisphere, with a density of `cos θ / π`
+#[inline]
+pub fn sample_cosine_hemisphere(u: Vec2) -> Vec3 {
+    // Malley's method: project a uniformly distributed point of the disk onto the hemisphere
+    let (x, y) = (u.x(), u.y());
+    let r = x.sqrt();
+    let phi = 2.0 * PI * y;
+    return Vec3::new(r * phi.cos(), r * phi.sin(), f32::sqrt(f32::max(0.0, 1.0 - x)));
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{object::Ray, random::Pcg32};
+
+    /// Hit on the xy plane, seen from above
+    pub fn hit() -> Hit {
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 0.0, -1.0).unit());
+        return Hit::new(ray, 1.0, Vec3::new(0.0, 0.0, 1.0).unit(), Vec2::ZERO, Vec3::new(1.0, 0.0, 0.0));
+    }
+
+    /// Checks that sampled directions are consistent with `eval` and `pdf`, and that the
+    /// material doesn't create energy
+    pub fn check_material(material: &dyn Material, wo: Vec3) {
+        let hit = hit();
+        let mut rng = Pcg32::new(1, 0);
+        let mut total = Vec3::ZERO;
+        const SAMPLES: usize = 20000;
+
+        for _ in 0..SAMPLES {
+            let Some(sample) = material.sample(&hit, wo, rng.next_vec2()) else { continue };
+            assert!((sample.wi.norm() - 1.0).abs() < 1e-3, "{:?}", sample.wi);
+            assert!(sample.pdf >= 0.0);
+
+            if !sample.specular && sample.pdf > 0.0 {
+                let pdf = material.pdf(&hit, wo, sample.wi);
+                assert!((pdf - sample.pdf).abs() <= 1e-3 * pdf.max(1.0), "{pdf} {}", sample.pdf);
+                let value = material.eval(&hit, wo, sample.wi);
+                assert!((value - sample.value).norm() <= 1e-3 * value.norm().max(1.0), "{value:?} {:?}", sample.value);
+            }
+            if sample.pdf > 0.0 {
+                total += sample.weight();
+            }
+        }
+
+        let albedo = total / SAMPLES as f32;
+        assert!(albedo.to_array().iter().all(|&x| x <= 1.02), "{albedo:?}");
+    }
+
+    #[test]
+    fn test_shading_frame() {
+        let frame = ShadingFrame::new(&hit());
+        let v = Vec3::new(0.3, -0.2, 0.9);
+        assert!((frame.to_world(frame.to_local(v)) - v).norm() < 1e-6);
+
+        let ray = Ray::new(Vec3::new(1.0, 2.0, 3.0), Vec3::new(-1.0, -2.0, -3.0).unit());
+        let normal = Vec3::new(1.0, 2.0, 3.0).unit();
+        let frame = ShadingFrame::new(&Hit::new(ray, 1.0, normal, Vec2::ZERO, Vec3::new(0.0, 1.0, 0.0)));
+        assert!((frame.to_local(normal.to_vec()) - Vec3::new(0.0, 0.0, 1.0)).norm() < 1e-6);
+    }
+}
@@ -0,0 +1,140 @@
+use super::{same_hemisphere, sample_cosine_hemisphere, BsdfSample, Material};
+use crate::{
+    math::{Vec2, Vec3},
+    object::Hit,
+};
+use std::f32::consts::{FRAC_1_PI, PI};
+
+/// Lambertian diffuse base under a Blinn-Phong specular lobe, normalized so that
+/// the highlight's brightness doesn't depend on its size
+// "Physically Based Rendering" (Pharr, Jakob, Humphreys) and Fabian Giesen's notes on Phong normalization
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlinnPhong {
+    pub diffuse: Vec3,
+    pub specular: Vec3,
+    /// Exponent of the specular lobe. The higher it is, the smaller and sharper highlights get.
+    pub shininess: f32,
+}
+
+impl BlinnPhong {
+    #[inline]
+    pub const fn new(diffuse: Vec3, specular: Vec3, shininess: f32) -> Self {
+        return Self {
+            diffuse,
+            specular,
+            shininess,
+        };
+    }
+
+    /// Diffuse color at the hit, tinted by its vertex color
+    #[inline]
+    fn diffuse(&self, hit: &Hit) -> Vec3 {
+        return hit.color.map_or(self.diffuse, |color| self.diffuse.wide_mul(color));
+    }
+
+    /// Probability of sampling the specular lobe instead of the diffuse one
+    #[inline]
+    fn specular_probability(&self, hit: &Hit) -> f32 {
+        let diffuse = self.diffuse(hit).to_array().into_iter().sum::<f32>();
+        let specular = self.specular.to_array().into_iter().sum::<f32>();
+        return match diffuse + specular > 0.0 {
+            true => specular / (diffuse + specular),
+            false => 0.0,
+        };
+    }
+
+    /// Density of the half vector, with respect to the solid angle of `wi`
+    #[inline]
+    fn specular_pdf(&self, wo: Vec3, wi: Vec3) -> f32 {
+        let h = (wo + wi).unit().to_vec();
+        let cos = h.z().abs();
+        return (self.shininess + 1.0) / (2.0 * PI) * cos.powf(self.shininess) / (4.0 * (wo * h).abs());
+    }
+}
+
+impl Material for BlinnPhong {
+    #[inline]
+    fn eval(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> Vec3 {
+        if !same_hemisphere(wo, wi) {
+            return Vec3::ZERO;
+        }
+
+        let h = (wo + wi).unit().to_vec();
+        let specular = (self.shininess + 8.0) / (8.0 * PI) * h.z().abs().powf(self.shininess);
+        return FRAC_1_PI * self.diffuse(hit) + specular * self.specular;
+    }
+
+    fn sample(&self, hit: &Hit, wo: Vec3, u: Vec2) -> Option<BsdfSample> {
+        let p = self.specular_probability(hit);
+        let wi = match u.x() < p {
+            // reflect around a half vector distributed as `cos^n θ`
+            true => {
+                let u = Vec2::new(u.x() / p, u.y());
+                let cos = u.x().powf(1.0 / (self.shininess + 1.0));
+                let sin = f32::sqrt(f32::max(0.0, 1.0 - cos * cos));
+                let phi = 2.0 * PI * u.y();
+                let h = Vec3::new(sin * phi.cos(), sin * phi.sin(), cos.copysign(wo.z()));
+                2.0 * (wo * h) * h - wo
+            }
+            false => {
+                let u = Vec2::new(f32::min((u.x() - p) / (1.0 - p), 1.0 - f32::EPSILON), u.y());
+                let mut wi = sample_cosine_hemisphere(u);
+                wi.set_z(wi.z().copysign(wo.z()));
+                wi
+            }
+        };
+
+        if !same_hemisphere(wo, wi) {
+            return None;
+        }
+
+        return Some(BsdfSample {
+            wi,
+            value: self.eval(hit, wo, wi),
+            pdf: self.pdf(hit, wo, wi),
+            specular: false,
+        });
+    }
+
+    #[inline]
+    fn pdf(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> f32 {
+        if !same_hemisphere(wo, wi) {
+            return 0.0;
+        }
+
+        let p = self.specular_probability(hit);
+        return p * self.specular_pdf(wo, wi) + (1.0 - p) * wi.z().abs() * FRAC_1_PI;
+    }
+
+    /// The diffuse color, as for the ambient term of the original Phong model
+    #[inline]
+    fn albedo(&self, hit: &Hit, _: Vec3) -> Vec3 {
+        return self.diffuse(hit);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_blinn_phong() {
+    use super::tests::{check_material, hit};
+
+    let material = BlinnPhong::new(Vec3::new(0.5, 0.2, 0.1), Vec3::splat(0.3), 40.0);
+    for wo in [Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.6, 0.0, 0.8), Vec3::new(0.0, -0.96, 0.28)] {
+        check_material(&material, wo);
+    }
+
+    // the highlight is centered on the mirror direction, and dims with the cosine to the light
+    let hit = hit();
+    let wo = Vec3::new(0.6, 0.0, 0.8);
+    let mirror = Vec3::new(-0.6, 0.0, 0.8);
+    let off = Vec3::new(-0.8, 0.0, 0.6);
+    assert!(material.eval(&hit, wo, mirror).x() > material.eval(&hit, wo, off).x());
+    assert_eq!(material.eval(&hit, wo, Vec3::new(0.0, 0.0, -1.0)), Vec3::ZERO);
+
+    // vertex colors tint the diffuse lobe
+    let colored = Hit {
+        color: Some(Vec3::new(0.0, 1.0, 1.0)),
+        ..hit
+    };
+    assert_eq!(material.albedo(&colored, wo), Vec3::new(0.0, 0.2, 0.1));
+}
@@ -0,0 +1,332 @@
+use super::{
+    fresnel_dielectric, same_hemisphere, sample_cosine_hemisphere, BsdfSample, Conductor, Dielectric, Ggx,
+    Material,
+};
+use crate::{
+    math::{Vec2, Vec3},
+    object::Hit,
+};
+use std::f32::consts::FRAC_1_PI;
+
+/// Index of refraction of the clearcoat layer, as for a polyurethane varnish
+const CLEARCOAT_IOR: f32 = 1.5;
+
+/// All-purpose material, with the parameters artists are used to from their authoring tools.
+///
+/// Surfaces blend between a metal and a dielectric base, which is either opaque (a diffuse body
+/// under a specular layer) or transmissive (glass), and may be varnished by a clearcoat.
+/// Every parameter but the colors lies in `[0, 1]`.
+// "Physically-Based Shading at Disney" (Burley, 2012) and the OpenPBR Surface specification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Principled {
+    /// Diffuse color of dielectrics, reflectance of metals, and tint of the transmitted light.
+    /// It's tinted by the vertex colors of the objects that have them.
+    pub base_color: Vec3,
+    /// Blends between a dielectric and a metal
+    pub metallic: f32,
+    /// Perceptual roughness of the specular reflections and transmission
+    pub roughness: f32,
+    /// Stretches the highlights along the tangent
+    pub anisotropy: f32,
+    /// Scales the reflections off the dielectric base, which are physically correct at 1
+    pub specular: f32,
+    /// Index of refraction of the dielectric base
+    pub ior: f32,
+    /// Strength of the retroreflective sheen of cloth at grazing angles
+    pub sheen: f32,
+    pub sheen_color: Vec3,
+    /// Coverage of the clearcoat
+    pub clearcoat: f32,
+    pub clearcoat_roughness: f32,
+    /// Blends between an opaque and a transmissive dielectric base
+    pub transmission: f32,
+    /// Radiance emitted by the surface
+    pub emission: Vec3,
+}
+
+impl Principled {
+    #[inline]
+    pub const fn new(base_color: Vec3) -> Self {
+        return Self {
+            base_color,
+            metallic: 0.0,
+            roughness: 0.5,
+            anisotropy: 0.0,
+            specular: 1.0,
+            ior: 1.5,
+            sheen: 0.0,
+            sheen_color: Vec3::splat(1.0),
+            clearcoat: 0.0,
+            clearcoat_roughness: 0.03,
+            transmission: 0.0,
+            emission: Vec3::ZERO,
+        };
+    }
+
+    /// Breaks the material into the lobes of its layers, weighted for light scattered towards `wo`
+    fn lobes(&self, hit: &Hit, wo: Vec3) -> Lobes {
+        let color = hit.color.map_or(self.base_color, |color| self.base_color.wide_mul(color));
+        let roughness = Ggx::from_anisotropic_roughness(self.roughness, self.anisotropy);
+        let cos = wo.z().abs();
+
+        let coat = Conductor::new(Vec3::splat(CLEARCOAT_IOR), Vec3::ZERO)
+            .with_roughness(Ggx::from_roughness(self.clearcoat_roughness));
+        let metal = Conductor::from_reflectance(color, Vec3::splat(1.0)).with_roughness(roughness);
+        let glass = Dielectric::new(self.ior).with_tint(color).with_roughness(roughness);
+        // a conductor that doesn't absorb light is a dielectric that doesn't transmit it
+        let specular = Conductor::new(Vec3::splat(self.ior), Vec3::ZERO).with_roughness(roughness);
+        let diffuse = Diffuse {
+            color,
+            sheen: self.sheen * self.sheen_color,
+        };
+
+        // light passing through the coat, and the specular layer of opaque dielectrics, feeds the layers below
+        let clearcoat = self.clearcoat.clamp(0.0, 1.0);
+        let metallic = self.metallic.clamp(0.0, 1.0);
+        let transmission = self.transmission.clamp(0.0, 1.0);
+        let coat_reflectance = clearcoat * fresnel_dielectric(cos, CLEARCOAT_IOR.recip());
+        let specular_reflectance = self.specular * fresnel_dielectric(cos, self.ior.recip());
+
+        let base = 1.0 - coat_reflectance;
+        let opaque = base * (1.0 - metallic) * (1.0 - transmission);
+        let weights = [
+            clearcoat,
+            base * metallic,
+            base * (1.0 - metallic) * transmission,
+            opaque * self.specular,
+            opaque * f32::max(0.0, 1.0 - specular_reflectance),
+        ];
+
+        // sample every lobe in proportion to a rough estimate of the light it scatters
+        let mean = |v: Vec3| v.to_array().into_iter().sum::<f32>() / 3.0;
+        let mut probabilities = [
+            coat_reflectance,
+            weights[1] * mean(color),
+            weights[2],
+            opaque * specular_reflectance,
+            weights[4] * mean(color + diffuse.sheen),
+        ];
+        let total = probabilities.iter().sum::<f32>();
+        for p in probabilities.iter_mut() {
+            *p = match total > 0.0 {
+                true => *p / total,
+                false => 0.0,
+            };
+        }
+
+        return Lobes {
+            coat,
+            metal,
+            glass,
+            specular,
+            diffuse,
+            weights,
+            probabilities,
+        };
+    }
+}
+
+impl Default for Principled {
+    #[inline]
+    fn default() -> Self {
+        return Self::new(Vec3::splat(0.8));
+    }
+}
+
+impl Material for Principled {
+    fn eval(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> Vec3 {
+        let lobes = self.lobes(hit, wo);
+        let mut result = Vec3::ZERO;
+        for (lobe, weight) in lobes.materials().into_iter().zip(lobes.weights) {
+            if weight > 0.0 {
+                result += weight * lobe.eval(hit, wo, wi);
+            }
+        }
+        return result;
+    }
+
+    fn sample(&self, hit: &Hit, wo: Vec3, u: Vec2) -> Option<BsdfSample> {
+        let lobes = self.lobes(hit, wo);
+
+        // pick a lobe with the first dimension, and reuse what's left of it to sample the lobe
+        let (mut start, mut picked) = (0.0, None);
+        for (i, &p) in lobes.probabilities.iter().enumerate().filter(|(_, &p)| p > 0.0) {
+            picked = Some((i, start, p));
+            if u.x() < start + p {
+                break;
+            }
+            start += p;
+        }
+        let (i, start, p) = picked?;
+        let u = Vec2::new(((u.x() - start) / p).clamp(0.0, 1.0 - f32::EPSILON), u.y());
+
+        let sample = lobes.materials()[i].sample(hit, wo, u)?;
+        if sample.specular {
+            return Some(BsdfSample {
+                value: lobes.weights[i] * sample.value,
+                pdf: p * sample.pdf,
+                ..sample
+            });
+        }
+
+        let pdf = self.pdf(hit, wo, sample.wi);
+        if !(pdf > 0.0) {
+            return None;
+        }
+        return Some(BsdfSample {
+            wi: sample.wi,
+            value: self.eval(hit, wo, sample.wi),
+            pdf,
+            specular: false,
+        });
+    }
+
+    fn pdf(&self, hit: &Hit, wo: Vec3, wi: Vec3) -> f32 {
+        let lobes = self.lobes(hit, wo);
+        let mut result = 0.0;
+        for (lobe, p) in lobes.materials().into_iter().zip(lobes.probabilities) {
+            if p > 0.0 {
+                result += p * lobe.pdf(hit, wo, wi);
+            }
+        }
+        return result;
+    }
+
+    #[inline]
+    fn emitted(&self, _: &Hit, _: Vec3) -> Vec3 {
+        return self.emission;
+    }
+}
+
+/// Layers of a [`Principled`] material, from top to bottom
+struct Lobes {
+    coat: Conductor,
+    metal: Conductor,
+    glass: Dielectric,
+    specular: Conductor,
+    diffuse: Diffuse,
+    /// Contribution of every lobe to the material
+    weights: [f32; 5],
+    /// Probability of sampling every lobe
+    probabilities: [f32; 5],
+}
+
+impl Lobes {
+    #[inline]
+    fn materials(&self) -> [&dyn Material; 5] {
+        return [&self.coat, &self.metal, &self.glass, &self.specular, &self.diffuse];
+    }
+}
+
+/// Lambertian body, with a sheen that brightens towards grazing angles
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Diffuse {
+    color: Vec3,
+    sheen: Vec3,
+}
+
+impl Material for Diffuse {
+    fn eval(&self, _: &Hit, wo: Vec3, wi: Vec3) -> Vec3 {
+        if !same_hemisphere(wo, wi) {
+            return Vec3::ZERO;
+        }
+
+        let wh = wo + wi;
+        let sheen = match wh == Vec3::ZERO {
+            true => 0.0,
+            false => (1.0 - (wi * wh.unit().to_vec()).abs()).powi(5),
+        };
+        return FRAC_1_PI * (self.color + sheen * self.sheen);
+    }
+
+    fn sample(&self, hit: &Hit, wo: Vec3, u: Vec2) -> Option<BsdfSample> {
+        let mut wi = sample_cosine_hemisphere(u);
+        if wo.z() < 0.0 {
+            wi.set_z(-wi.z());
+        }
+
+        return Some(BsdfSample {
+            wi,
+            value: self.eval(hit, wo, wi),
+            pdf: wi.z().abs() * FRAC_1_PI,
+            specular: false,
+        });
+    }
+
+    #[inline]
+    fn pdf(&self, _: &Hit, wo: Vec3, wi: Vec3) -> f32 {
+        if !same_hemisphere(wo, wi) {
+            return 0.0;
+        }
+        return wi.z().abs() * FRAC_1_PI;
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_principled() {
+    use super::tests::{check_material, hit};
+
+    let red = Vec3::new(0.8, 0.1, 0.1);
+    let materials = [
+        Principled::new(red),
+        Principled {
+            metallic: 1.0,
+            roughness: 0.3,
+            ..Principled::new(Vec3::new(1.0, 0.8, 0.3))
+        },
+        Principled {
+            metallic: 0.5,
+            anisotropy: 0.7,
+            sheen: 0.5,
+            clearcoat: 1.0,
+            clearcoat_roughness: 0.2,
+            ..Principled::new(red)
+        },
+        Principled {
+            transmission: 1.0,
+            roughness: 0.2,
+            ..Principled::new(Vec3::splat(1.0))
+        },
+        // smooth layers sample Dirac deltas, alongside the diffuse lobe
+        Principled {
+            roughness: 0.0,
+            clearcoat: 0.5,
+            clearcoat_roughness: 0.0,
+            transmission: 0.5,
+            ..Principled::new(red)
+        },
+    ];
+    for material in materials {
+        for wo in [Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.6, 0.0, 0.8), Vec3::new(0.0, -0.96, 0.28)] {
+            check_material(&material, wo);
+        }
+    }
+
+    // without metal, sheen, transmission or coat, it's a Lambertian body under a specular layer
+    let hit = hit();
+    let (wo, wi) = (Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.8, 0.0, 0.6));
+    let diffuse = Principled {
+        specular: 0.0,
+        ..Principled::new(red)
+    };
+    assert!((diffuse.eval(&hit, wo, wi) - FRAC_1_PI * red).norm() < 1e-6);
+    let plastic = Principled::new(red);
+    assert!(plastic.eval(&hit, wo, wo).y() > diffuse.eval(&hit, wo, wo).y());
+
+    // metals reflect their base color at normal incidence
+    let gold = Principled {
+        metallic: 1.0,
+        roughness: 0.0,
+        ..Principled::new(Vec3::new(1.0, 0.8, 0.3))
+    };
+    let sample = gold.sample(&hit, wo, Vec2::new(0.5, 0.5)).unwrap();
+    assert!(sample.specular && (sample.weight() - Vec3::new(0.999, 0.8, 0.3)).norm() < 1e-3);
+    assert_eq!(gold.eval(&hit, wo, wi), Vec3::ZERO);
+
+    let emissive = Principled {
+        emission: Vec3::splat(2.0),
+        ..Principled::default()
+    };
+    assert_eq!(emissive.emitted(&hit, wo), Vec3::splat(2.0));
+}
@@ -0,0 +1,98 @@
+use super::Vec3;
+
+/// Axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    /// Box that contains nothing, and is the identity of [`Aabb::union`]
+    pub const EMPTY: Self = Self::new(Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY));
+    /// Box that contains everything
+    pub const INFINITE: Self = Self::new(Vec3::splat(f32::NEG_INFINITY), Vec3::splat(f32::INFINITY));
+
+    #[inline]
+    pub const fn new(min: Vec3, max: Vec3) -> Self {
+        return Self { min, max }
+    }
+
+    #[inline]
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Self {
+        return points.into_iter().fold(Self::EMPTY, Self::include)
+    }
+
+    #[inline]
+    pub fn include(self, point: Vec3) -> Self {
+        return Self::new(self.min.min(point), self.max.max(point))
+    }
+
+    #[inline]
+    pub fn union(self, other: Self) -> Self {
+        return Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        let [x, y, z] = (self.max - self.min).to_array();
+        return x < 0.0 || y < 0.0 || z < 0.0
+    }
+
+    #[inline]
+    pub fn is_finite(self) -> bool {
+        return self.min.is_finite() && self.max.is_finite()
+    }
+
+    #[inline]
+    pub fn extent(self) -> Vec3 {
+        return self.max - self.min
+    }
+
+    #[inline]
+    pub fn centroid(self) -> Vec3 {
+        return 0.5 * (self.min + self.max)
+    }
+
+    #[inline]
+    pub fn surface_area(self) -> f32 {
+        if self.is_empty() { return 0.0 }
+        let [x, y, z] = self.extent().to_array();
+        return 2.0 * (x * y + y * z + z * x)
+    }
+
+    /// Index of the axis along which the box is the widest
+    #[inline]
+    pub fn longest_axis(self) -> usize {
+        return match self.extent().to_array() {
+            [x, y, z] if x >= y && x >= z => 0,
+            [_, y, z] if y >= z => 1,
+            _ => 2,
+        }
+    }
+
+    /// Returns the distance at which a ray enters the box, if it does so within `[t_min, t_max]`.
+    ///
+    /// `inv_direction` is the component-wise inverse of the ray's direction.
+    // https://tavianator.com/2022/ray_box_boundary.html
+    #[inline]
+    pub fn intersect(self, origin: Vec3, inv_direction: Vec3, t_min: f32, t_max: f32) -> Option<f32> {
+        let t0 = (self.min - origin).wide_mul(inv_direction);
+        let t1 = (self.max - origin).wide_mul(inv_direction);
+
+        let enter = t0.min(t1).to_array().into_iter().fold(t_min, f32::max);
+        let exit = t0.max(t1).to_array().into_iter().fold(t_max, f32::min);
+
+        if enter <= exit {
+            return Some(enter)
+        }
+        return None
+    }
+}
+
+impl Default for Aabb {
+    #[inline]
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
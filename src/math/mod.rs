@@ -1,6 +1,7 @@
 flat_mod! { vec2, vec3, vec4 }
 flat_mod! { mat4 }
 flat_mod! { euler, quat }
+flat_mod! { aabb }
 
 /// Describes a tranformation in 3D-space
 #[derive(Debug, Clone, Copy, PartialEq)]
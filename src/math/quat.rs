@@ -1,4 +1,4 @@
-use super::{Vec3, Vec4, EulerAngles};
+use super::{UnitVec3, Vec3, Vec4, EulerAngles};
 use std::{
     fmt::Debug,
     ops::{Add, Div, Mul, Sub, AddAssign, SubAssign, MulAssign, DivAssign},
@@ -112,7 +112,7 @@ impl Quaternion {
 impl Versor {
     #[inline]
     pub fn new (q: Quaternion) -> Option<Self> {
-        if q.sq_norm() == 1. {
+        if f32::abs(q.sq_norm() - 1.) <= f32::EPSILON {
             return Some(Self(q))
         }
         return None
@@ -120,7 +120,7 @@ impl Versor {
 
     #[inline]
     pub unsafe fn new_unchecked (q: Quaternion) -> Self {
-        debug_assert_eq!(q.sq_norm(), 1.);
+        debug_assert!(f32::abs(q.sq_norm() - 1.) <= f32::EPSILON);
         return Self(q)
     }
     
@@ -134,6 +134,31 @@ impl Versor {
         Self(Quaternion::from_euler(euler))
     }
 
+    /// Rotation that maps the x, y and z axes onto the given right-handed orthonormal basis
+    // https://www.euclideanspace.com/maths/geometry/rotations/conversions/matrixToQuaternion/
+    pub fn from_basis (x: UnitVec3, y: UnitVec3, z: UnitVec3) -> Self {
+        let [m00, m10, m20] = x.to_array();
+        let [m01, m11, m21] = y.to_array();
+        let [m02, m12, m22] = z.to_array();
+        let trace = m00 + m11 + m22;
+
+        let q = if trace > 0.0 {
+            let s = 2.0 * f32::sqrt(trace + 1.0);
+            Quaternion::new(0.25 * s, (m21 - m12) / s, (m02 - m20) / s, (m10 - m01) / s)
+        } else if m00 > m11 && m00 > m22 {
+            let s = 2.0 * f32::sqrt(1.0 + m00 - m11 - m22);
+            Quaternion::new((m21 - m12) / s, 0.25 * s, (m01 + m10) / s, (m02 + m20) / s)
+        } else if m11 > m22 {
+            let s = 2.0 * f32::sqrt(1.0 + m11 - m00 - m22);
+            Quaternion::new((m02 - m20) / s, (m01 + m10) / s, 0.25 * s, (m12 + m21) / s)
+        } else {
+            let s = 2.0 * f32::sqrt(1.0 + m22 - m00 - m11);
+            Quaternion::new((m10 - m01) / s, (m02 + m20) / s, (m12 + m21) / s, 0.25 * s)
+        };
+
+        return Self::from_quaternion(q)
+    }
+
     #[inline]
     pub const fn to_inner (self) -> Quaternion {
         self.0
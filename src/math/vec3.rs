@@ -114,6 +114,18 @@ impl Vec3 {
         }
     }
 
+    /// Component-wise minimum
+    #[inline]
+    pub fn min(self, rhs: Self) -> Self {
+        Self(self.0.simd_min(rhs.0))
+    }
+
+    /// Component-wise maximum
+    #[inline]
+    pub fn max(self, rhs: Self) -> Self {
+        Self(self.0.simd_max(rhs.0))
+    }
+
     #[inline]
     pub fn reduce_add(self) -> f32 {
         cfg_if::cfg_if! {
@@ -168,12 +180,12 @@ impl Vec3 {
         //     let sub = _mm_sub_ps(lhszxy_rhs, rhszxy_lhs);
         //     Self(_mm_shuffle_ps(sub, sub, 0b01_01_00_10))
 
-        let lhszxy = simd_swizzle!(self.0, [1, 1, 0, 0]);
-        let rhszxy = simd_swizzle!(self.0, [1, 1, 0, 2]);
+        let lhszxy = simd_swizzle!(self.0, [2, 0, 1, 3]);
+        let rhszxy = simd_swizzle!(rhs.0, [2, 0, 1, 3]);
         let lhszxy_rhs = lhszxy * rhs.0;
         let rhszxy_lhs = rhszxy * self.0;
         let sub = lhszxy_rhs - rhszxy_lhs;
-        return Self(simd_swizzle!(sub, [1, 1, 0, 2]));
+        return Self(simd_swizzle!(sub, [2, 0, 1, 3]));
     }
 
     #[inline]
@@ -329,6 +341,23 @@ impl UnitVec3 {
         (self - rhs).norm()
     }
 
+    /// Returns two unit vectors that, together with `self`, form a right-handed orthonormal basis
+    // https://graphics.pixar.com/library/OrthonormalB/paper.pdf
+    #[inline]
+    pub fn orthonormal_basis (self) -> (UnitVec3, UnitVec3) {
+        let [x, y, z] = self.to_array();
+        let sign = f32::copysign(1.0, z);
+        let a = -1.0 / (sign + z);
+        let b = x * y * a;
+
+        unsafe {
+            (
+                UnitVec3::new_unchecked(1.0 + sign * x * x * a, sign * b, -sign * x),
+                UnitVec3::new_unchecked(b, sign + y * y * a, -y),
+            )
+        }
+    }
+
     #[inline]
     pub fn angle (self, rhs: Self) -> f32 {
         f32::acos(self.dot(rhs))
@@ -711,4 +740,14 @@ impl Debug for Vec3 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         Debug::fmt(&self.as_array(), f)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+#[test]
+fn test_cross() {
+    let x = Vec3::new(1., 0., 0.);
+    let y = Vec3::new(0., 1., 0.);
+    assert_eq!(x.cross(y), Vec3::new(0., 0., 1.));
+    assert_eq!(y.cross(x), Vec3::new(0., 0., -1.));
+    assert_eq!(Vec3::new(1., 2., 3.).cross(Vec3::new(4., 5., 6.)), Vec3::new(-3., 6., -3.));
+}
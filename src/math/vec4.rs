@@ -59,7 +59,7 @@ impl Vec4 {
     #[inline]
     pub fn from_vec3(xyz: Vec3, w: f32) -> Self {
         let mut this = xyz.to_inner();
-        this[2] = w;
+        this[3] = w;
         return Self::from_simd(this);
     }
 
@@ -0,0 +1,60 @@
+use super::{plane::intersect_plane, Hit, Object, Ray};
+use crate::math::{Aabb, UnitVec3, Vec2, Vec3};
+use std::f32::consts::{FRAC_1_PI, PI};
+
+/// Flat disk centered at `center`, facing towards `normal`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Disk {
+    pub center: Vec3,
+    pub normal: UnitVec3,
+    pub radius: f32,
+}
+
+impl Disk {
+    #[inline]
+    pub const fn new(center: Vec3, normal: UnitVec3, radius: f32) -> Self {
+        return Self { center, normal, radius }
+    }
+}
+
+impl Object for Disk {
+    #[inline]
+    fn hit(&self, ray: Ray, t_min: f32, t_max: f32) -> Option<Hit> {
+        let time = intersect_plane(self.center, self.normal, ray, t_min, t_max)?;
+        let offset = ray.position_at(time) - self.center;
+
+        let sq_dist = offset.sq_norm();
+        if sq_dist > self.radius * self.radius {
+            return None
+        }
+
+        // u goes around the disk, v from the center to the rim
+        let (tangent, bitangent) = self.normal.orthonormal_basis();
+        let phi = f32::atan2(bitangent * offset, tangent * offset);
+        let uv = Vec2::new(0.5 * (phi + PI) * FRAC_1_PI, sq_dist.sqrt() / self.radius);
+
+        return Some(Hit::new(ray, time, self.normal, uv, self.normal.cross_vec(offset)));
+    }
+
+    #[inline]
+    fn bounds(&self) -> Aabb {
+        // the disk spans `radius * sin(angle between the normal and the axis)` along each axis
+        let extent = self.normal.to_array().map(|n| self.radius * f32::sqrt(f32::max(1.0 - n * n, 0.0)));
+        let extent = Vec3::from_array(extent);
+        return Aabb::new(self.center - extent, self.center + extent)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_disk_hit() {
+    let disk = Disk::new(Vec3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0).unit(), 1.0);
+
+    let ray = Ray::new(Vec3::new(0.5, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0).unit());
+    let hit = disk.hit(ray, 0.0, f32::INFINITY).unwrap();
+    assert_eq!(hit.t, 5.0);
+    assert_eq!(hit.uv.y(), 0.5);
+
+    let ray = Ray::new(Vec3::new(1.5, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0).unit());
+    assert!(disk.hit(ray, 0.0, f32::INFINITY).is_none());
+}
@@ -0,0 +1,70 @@
+use super::Ray;
+use crate::math::{UnitVec3, Vec2, Vec3};
+
+/// Information about the intersection between a ray and an object
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+    /// Distance along the ray at which the intersection occurs
+    pub t: f32,
+    pub position: Vec3,
+    /// Normal used for shading, always facing against the incoming ray
+    pub normal: UnitVec3,
+    /// Normal of the underlying geometry, always pointing outwards of the surface
+    pub geometric_normal: UnitVec3,
+    /// `true` if the ray hit the outer side of the surface
+    pub front_face: bool,
+    pub uv: Vec2,
+    /// Tangent of the surface, orthogonal to `normal`
+    pub tangent: UnitVec3,
+    /// Completes the right-handed `(tangent, bitangent, normal)` shading frame
+    pub bitangent: UnitVec3,
+    /// Vertex color at the hit point, for objects that have them
+    pub color: Option<Vec3>,
+}
+
+impl Hit {
+    /// Builds the hit record of `ray` at distance `t`.
+    ///
+    /// `tangent` doesn't need to be orthogonal to the normal, nor normalized. If it's
+    /// degenerate, an arbitrary tangent is chosen instead.
+    #[inline]
+    pub fn new(ray: Ray, t: f32, outward_normal: UnitVec3, uv: Vec2, tangent: Vec3) -> Self {
+        return Self::with_shading_normal(ray, t, outward_normal, outward_normal, uv, tangent);
+    }
+
+    /// Builds the hit record of `ray` at distance `t`, shading it with a normal other than
+    /// the geometric one (i.e. interpolated vertex normals).
+    #[inline]
+    pub fn with_shading_normal(
+        ray: Ray,
+        t: f32,
+        geometric_normal: UnitVec3,
+        shading_normal: UnitVec3,
+        uv: Vec2,
+        tangent: Vec3,
+    ) -> Self {
+        let front_face = ray.direction * geometric_normal < 0.0;
+        let normal = match front_face {
+            true => shading_normal,
+            false => -shading_normal,
+        };
+
+        let tangent = tangent - (normal * tangent) * normal;
+        let tangent = match tangent.sq_norm() {
+            x if x.is_normal() => tangent.unit(),
+            _ => normal.orthonormal_basis().0,
+        };
+
+        return Self {
+            t,
+            position: ray.position_at(t),
+            normal,
+            geometric_normal,
+            front_face,
+            uv,
+            tangent,
+            bitangent: normal.cross(tangent),
+            color: None,
+        };
+    }
+}
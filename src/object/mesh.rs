@@ -0,0 +1,204 @@
+use super::{
+    triangle::{Triangle, TriangleHit},
+    Hit, Object, Ray,
+};
+use crate::{
+    bvh::Bvh,
+    math::{Aabb, UnitVec3, Vec2, Vec3},
+};
+
+/// Triangle mesh with shared vertex buffers, indexed by triangle.
+///
+/// Triangles are kept in an internal [`Bvh`], so intersecting the mesh is logarithmic on its size.
+#[derive(Debug, Clone)]
+pub struct TriangleMesh {
+    positions: Box<[Vec3]>,
+    normals: Option<Box<[UnitVec3]>>,
+    uvs: Option<Box<[Vec2]>>,
+    colors: Option<Box<[Vec3]>>,
+    indices: Box<[[u32; 3]]>,
+    bvh: Bvh,
+}
+
+impl TriangleMesh {
+    /// Creates a new mesh, with every triangle defined by the indices of its vertices.
+    ///
+    /// # Panics
+    /// Panics if any index is out of bounds.
+    pub fn new(positions: impl Into<Box<[Vec3]>>, indices: impl Into<Box<[[u32; 3]]>>) -> Self {
+        let positions = positions.into();
+        let indices = indices.into();
+        assert!(
+            indices.iter().flatten().all(|&i| (i as usize) < positions.len()),
+            "vertex index out of bounds"
+        );
+
+        let bounds = indices
+            .iter()
+            .map(|face| Aabb::from_points(face.map(|i| positions[i as usize])))
+            .collect::<Vec<_>>();
+
+        return Self {
+            bvh: Bvh::new(&bounds),
+            positions,
+            normals: None,
+            uvs: None,
+            colors: None,
+            indices,
+        };
+    }
+
+    /// Sets the per-vertex normals, which are interpolated across each triangle.
+    ///
+    /// # Panics
+    /// Panics if there isn't exactly one normal per vertex.
+    pub fn with_normals(self, normals: impl Into<Box<[UnitVec3]>>) -> Self {
+        let normals = normals.into();
+        assert_eq!(normals.len(), self.positions.len(), "expected one normal per vertex");
+        return Self {
+            normals: Some(normals),
+            ..self
+        };
+    }
+
+    /// Sets the per-vertex uv coordinates.
+    ///
+    /// # Panics
+    /// Panics if there isn't exactly one uv coordinate per vertex.
+    pub fn with_uvs(self, uvs: impl Into<Box<[Vec2]>>) -> Self {
+        let uvs = uvs.into();
+        assert_eq!(uvs.len(), self.positions.len(), "expected one uv coordinate per vertex");
+        return Self {
+            uvs: Some(uvs),
+            ..self
+        };
+    }
+
+    /// Sets the per-vertex colors.
+    ///
+    /// # Panics
+    /// Panics if there isn't exactly one color per vertex.
+    pub fn with_colors(self, colors: impl Into<Box<[Vec3]>>) -> Self {
+        let colors = colors.into();
+        assert_eq!(colors.len(), self.positions.len(), "expected one color per vertex");
+        return Self {
+            colors: Some(colors),
+            ..self
+        };
+    }
+
+    /// Computes smooth per-vertex normals, as the area-weighted average of the normals
+    /// of the triangles sharing each vertex.
+    pub fn with_smooth_normals(self) -> Self {
+        let mut normals = vec![Vec3::ZERO; self.positions.len()];
+        for face in self.indices.iter() {
+            let [a, b, c] = face.map(|i| self.positions[i as usize]);
+            // the cross product's norm is twice the area of the triangle
+            let normal = (b - a).cross(c - a);
+            for &i in face {
+                normals[i as usize] += normal;
+            }
+        }
+
+        let normals = normals
+            .into_iter()
+            .map(|x| match x.sq_norm().is_normal() {
+                true => x.unit(),
+                false => Vec3::new(0.0, 1.0, 0.0).unit(),
+            })
+            .collect::<Vec<_>>();
+
+        return self.with_normals(normals);
+    }
+
+    #[inline]
+    pub fn positions(&self) -> &[Vec3] {
+        return &self.positions;
+    }
+
+    #[inline]
+    pub fn normals(&self) -> Option<&[UnitVec3]> {
+        return self.normals.as_deref();
+    }
+
+    #[inline]
+    pub fn uvs(&self) -> Option<&[Vec2]> {
+        return self.uvs.as_deref();
+    }
+
+    #[inline]
+    pub fn colors(&self) -> Option<&[Vec3]> {
+        return self.colors.as_deref();
+    }
+
+    #[inline]
+    pub fn indices(&self) -> &[[u32; 3]] {
+        return &self.indices;
+    }
+
+    /// Number of triangles in the mesh
+    #[inline]
+    pub fn len(&self) -> usize {
+        return self.indices.len();
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        return self.indices.is_empty();
+    }
+
+    /// Returns the `i`-th triangle of the mesh
+    #[inline]
+    pub fn triangle(&self, i: usize) -> Triangle {
+        let face = self.indices[i].map(|x| x as usize);
+        return Triangle {
+            vertices: face.map(|x| self.positions[x]),
+            normals: self.normals.as_ref().map(|normals| face.map(|x| normals[x])),
+            uvs: self.uvs.as_ref().map(|uvs| face.map(|x| uvs[x])),
+            colors: self.colors.as_ref().map(|colors| face.map(|x| colors[x])),
+        };
+    }
+}
+
+impl Object for TriangleMesh {
+    #[inline]
+    fn hit(&self, ray: Ray, t_min: f32, t_max: f32) -> Option<Hit> {
+        let (triangle, hit): (Triangle, TriangleHit) = self.bvh.traverse(ray, t_min, t_max, |i, t_max| {
+            let triangle = self.triangle(i);
+            let hit = triangle.intersect(ray, t_min, t_max)?;
+            return Some(((triangle, hit), hit.t));
+        })?;
+
+        return Some(triangle.hit_info(ray, hit));
+    }
+
+    #[inline]
+    fn bounds(&self) -> Aabb {
+        return self.bvh.bounds();
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_mesh_hit() {
+    // unit quad on the xy plane, split along its diagonal
+    let mesh = TriangleMesh::new(
+        vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+        ],
+        vec![[0, 1, 2], [0, 2, 3]],
+    )
+    .with_smooth_normals();
+
+    let ray = Ray::new(Vec3::new(0.25, 0.75, 2.0), Vec3::new(0.0, 0.0, -1.0).unit());
+    let hit = mesh.hit(ray, 0.0, f32::INFINITY).unwrap();
+    assert_eq!(hit.t, 2.0);
+    assert_eq!(hit.position, Vec3::new(0.25, 0.75, 0.0));
+    assert_eq!(hit.normal, Vec3::new(0.0, 0.0, 1.0).unit());
+
+    let ray = Ray::new(Vec3::new(1.5, 0.5, 2.0), Vec3::new(0.0, 0.0, -1.0).unit());
+    assert!(mesh.hit(ray, 0.0, f32::INFINITY).is_none());
+}
@@ -1,47 +1,54 @@
-use crate::{math::{UnitVec3, Vec3}};
+use crate::{math::{Aabb, UnitVec3, Vec3}};
 use std::sync::Arc;
+flat_mod! { hit }
 pub mod sphere;
+pub mod plane;
+pub mod disk;
+pub mod triangle;
+pub mod mesh;
 
 pub type DynObject<'a> = Box<dyn 'a + Object>;
 
 pub trait Object: Send + Sync {
-    fn normal(&self, at: Vec3) -> UnitVec3;
-    fn is_hit_by(&self, ray: Ray) -> Option<f32>;
+    /// Returns the closest intersection of `ray` with the object whose distance lies within `[t_min, t_max]`
+    fn hit(&self, ray: Ray, t_min: f32, t_max: f32) -> Option<Hit>;
+    /// Box enclosing the whole object, or [`Aabb::INFINITE`] if it's unbounded
+    fn bounds(&self) -> Aabb;
 }
 
 impl<T: ?Sized + Object> Object for &T {
     #[inline]
-    fn normal(&self, at: Vec3) -> UnitVec3 {
-        T::normal(*self, at)
+    fn hit(&self, ray: Ray, t_min: f32, t_max: f32) -> Option<Hit> {
+        T::hit(*self, ray, t_min, t_max)
     }
 
     #[inline]
-    fn is_hit_by(&self, ray: Ray) -> Option<f32> {
-        T::is_hit_by(*self, ray)
+    fn bounds(&self) -> Aabb {
+        T::bounds(*self)
     }
 }
 
 impl<T: ?Sized + Object> Object for Box<T> {
     #[inline]
-    fn normal(&self, at: Vec3) -> UnitVec3 {
-        T::normal(self, at)
+    fn hit(&self, ray: Ray, t_min: f32, t_max: f32) -> Option<Hit> {
+        T::hit(self, ray, t_min, t_max)
     }
 
     #[inline]
-    fn is_hit_by(&self, ray: Ray) -> Option<f32> {
-        T::is_hit_by(self, ray)
+    fn bounds(&self) -> Aabb {
+        T::bounds(self)
     }
 }
 
 impl<T: ?Sized + Object> Object for Arc<T> {
     #[inline]
-    fn normal(&self, at: Vec3) -> UnitVec3 {
-        T::normal(self, at)
+    fn hit(&self, ray: Ray, t_min: f32, t_max: f32) -> Option<Hit> {
+        T::hit(self, ray, t_min, t_max)
     }
 
     #[inline]
-    fn is_hit_by(&self, ray: Ray) -> Option<f32> {
-        T::is_hit_by(self, ray)
+    fn bounds(&self) -> Aabb {
+        T::bounds(self)
     }
 }
 
@@ -57,20 +64,74 @@ impl Ray {
         return Self { origin, direction };
     }
 
+    /// Creates a ray leaving the surface at `hit` towards `direction`.
+    ///
+    /// The origin is offset along the geometric normal, to the side `direction` points to,
+    /// so that the new ray doesn't intersect the surface it starts on.
+    #[inline]
+    pub fn spawn(hit: &Hit, direction: UnitVec3) -> Self {
+        let normal = match direction * hit.geometric_normal < 0.0 {
+            true => -hit.geometric_normal,
+            false => hit.geometric_normal,
+        };
+        return Self::new(Self::offset_origin(hit.position, normal), direction);
+    }
+
+    /// Offsets `position` along `normal` by the smallest amount that keeps it clear
+    /// of the surface, taking floating point error into account.
+    // https://link.springer.com/content/pdf/10.1007/978-1-4842-4427-2_6.pdf
+    #[inline]
+    pub fn offset_origin(position: Vec3, normal: UnitVec3) -> Vec3 {
+        const ORIGIN: f32 = 1.0 / 32.0;
+        const FLOAT_SCALE: f32 = 1.0 / 65536.0;
+        const INT_SCALE: f32 = 256.0;
+
+        let mut result = position.to_array();
+        for (p, n) in result.iter_mut().zip(normal.to_array()) {
+            *p = match f32::abs(*p) < ORIGIN {
+                true => *p + FLOAT_SCALE * n,
+                false => {
+                    let offset = (INT_SCALE * n) as i32;
+                    let offset = if *p < 0.0 { -offset } else { offset };
+                    f32::from_bits((p.to_bits() as i32).wrapping_add(offset) as u32)
+                }
+            }
+        }
+
+        return Vec3::from_array(result);
+    }
+
     #[inline]
     pub fn position_at(self, t: f32) -> Vec3 {
         return self.origin + t * self.direction;
     }
 
+    /// Returns the closest intersection in front of the ray's origin
     #[inline]
-    pub fn hits<T: Object>(self, target: &T) -> Option<f32> {
-        T::is_hit_by(target, self)
+    pub fn hits<T: Object>(self, target: &T) -> Option<Hit> {
+        T::hit(target, self, 0.0, f32::INFINITY)
     }
 
     #[inline]
     pub fn reflect(self, normal: UnitVec3) -> UnitVec3 {
         Vec3::unit(self.direction - 2. * (self.direction * normal) * normal)
     }
+
+    /// Direction of the ray once refracted through a surface, following Snell's law.
+    ///
+    /// `normal` faces against the ray, and `eta` is the ratio between the index of refraction
+    /// of the medium the ray comes from and the one it enters. Returns `None` on total internal reflection.
+    #[inline]
+    pub fn refract(self, normal: UnitVec3, eta: f32) -> Option<UnitVec3> {
+        let cos_i = -(self.direction * normal);
+        let sin2_t = eta * eta * f32::max(0.0, 1.0 - cos_i * cos_i);
+        if sin2_t >= 1.0 {
+            return None;
+        }
+
+        let cos_t = f32::sqrt(1.0 - sin2_t);
+        return Some(Vec3::unit(eta * self.direction + (eta * cos_i - cos_t) * normal));
+    }
 }
 
 #[cfg(test)]
@@ -87,7 +148,40 @@ mod tests {
         let ray = Ray::new(Vec3::new(0.0, 0.0, -5.0), unsafe {
             UnitVec3::new_unchecked(0.0, 0.0, 1.0)
         });
-        let hit = sphere.is_hit_by(ray);
-        assert_eq!(hit.unwrap(), 4.0);
+        let hit = sphere.hit(ray, 0.0, f32::INFINITY).unwrap();
+        assert_eq!(hit.t, 4.0);
+        assert_eq!(hit.position, Vec3::new(0.0, 0.0, -1.0));
+        assert!(sphere.hit(ray, 0.0, 3.0).is_none());
+        assert_eq!(sphere.hit(ray, 5.0, f32::INFINITY).unwrap().t, 6.0);
+    }
+
+    #[test]
+    fn test_refract() {
+        let normal = Vec3::new(0.0, 1.0, 0.0).unit();
+        let ray = Ray::new(Vec3::ZERO, Vec3::new(0.6, -0.8, 0.0).unit());
+
+        // Snell's law: eta * sin(i) = sin(t)
+        let refracted = ray.refract(normal, 1.0 / 1.5).unwrap();
+        assert!((refracted.x() - 0.6 / 1.5).abs() < 1e-6);
+        assert!(refracted.y() < 0.0);
+        assert_eq!(ray.refract(normal, 1.0).unwrap(), ray.direction);
+
+        // leaving the denser medium, within and past the critical angle
+        assert!(ray.refract(normal, 1.5).is_some());
+        let grazing = Ray::new(Vec3::ZERO, Vec3::new(0.8, -0.6, 0.0).unit());
+        assert!(grazing.refract(normal, 1.5).is_none());
+    }
+
+    #[test]
+    fn test_spawn_avoids_self_hit() {
+        let sphere = Sphere::new(Vec3::new(0.3, -0.2, 7.0), 2.0);
+        let ray = Ray::new(Vec3::ZERO, Vec3::new(0.1, 0.05, 1.0).unit());
+        let hit = ray.hits(&sphere).unwrap();
+
+        let reflected = Ray::spawn(&hit, ray.reflect(hit.normal));
+        assert!(reflected.hits(&sphere).is_none());
+
+        let refracted = Ray::spawn(&hit, ray.direction);
+        assert!(refracted.hits(&sphere).unwrap().t > 1.0);
     }
 }
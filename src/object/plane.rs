@@ -0,0 +1,68 @@
+use super::{Hit, Object, Ray};
+use crate::math::{Aabb, UnitVec3, Vec2, Vec3};
+
+/// Infinite plane going through `point`, facing towards `normal`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    pub point: Vec3,
+    pub normal: UnitVec3,
+}
+
+impl Plane {
+    #[inline]
+    pub const fn new(point: Vec3, normal: UnitVec3) -> Self {
+        return Self { point, normal }
+    }
+}
+
+// https://en.wikipedia.org/wiki/Line%E2%80%93plane_intersection
+impl Object for Plane {
+    #[inline]
+    fn hit(&self, ray: Ray, t_min: f32, t_max: f32) -> Option<Hit> {
+        let time = intersect_plane(self.point, self.normal, ray, t_min, t_max)?;
+
+        // uv coordinates are measured in world units along the plane's tangent frame
+        let (tangent, bitangent) = self.normal.orthonormal_basis();
+        let offset = ray.position_at(time) - self.point;
+        let uv = Vec2::new(tangent * offset, bitangent * offset);
+
+        return Some(Hit::new(ray, time, self.normal, uv, tangent.to_vec()));
+    }
+
+    #[inline]
+    fn bounds(&self) -> Aabb {
+        return Aabb::INFINITE
+    }
+}
+
+/// Returns the distance at which `ray` crosses the plane, if it lies within `[t_min, t_max]`
+#[inline]
+pub(super) fn intersect_plane(point: Vec3, normal: UnitVec3, ray: Ray, t_min: f32, t_max: f32) -> Option<f32> {
+    let denom = ray.direction * normal;
+    if denom == 0.0 { return None }
+
+    let time = (normal * (point - ray.origin)) / denom;
+    if (t_min..=t_max).contains(&time) {
+        return Some(time)
+    }
+    return None
+}
+
+#[cfg(test)]
+#[test]
+fn test_plane_hit() {
+    let plane = Plane::new(Vec3::new(0.0, -1.0, 0.0), Vec3::new(0.0, 1.0, 0.0).unit());
+
+    let ray = Ray::new(Vec3::new(3.0, 1.0, -2.0), Vec3::new(0.0, -1.0, 0.0).unit());
+    let hit = plane.hit(ray, 0.0, f32::INFINITY).unwrap();
+    assert_eq!(hit.t, 2.0);
+    assert!(hit.front_face);
+
+    let ray = Ray::new(Vec3::new(3.0, -2.0, -2.0), Vec3::new(0.0, 1.0, 0.0).unit());
+    let hit = plane.hit(ray, 0.0, f32::INFINITY).unwrap();
+    assert!(!hit.front_face);
+    assert_eq!(hit.normal, Vec3::new(0.0, -1.0, 0.0).unit());
+
+    let ray = Ray::new(Vec3::ZERO, Vec3::new(1.0, 0.0, 0.0).unit());
+    assert!(plane.hit(ray, 0.0, f32::INFINITY).is_none());
+}
@@ -1,6 +1,6 @@
-use super::{Object, Ray};
-use crate::math::{Vec3, UnitVec3};
-use std::cmp::Ordering;
+use super::{Hit, Object, Ray};
+use crate::math::{Aabb, Vec2, Vec3};
+use std::f32::consts::{FRAC_1_PI, PI};
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct Sphere {
@@ -18,29 +18,35 @@ impl Sphere {
 // https://en.wikipedia.org/wiki/Line%E2%80%93sphere_intersection
 impl Object for Sphere {
     #[inline]
-    fn normal (&self, at: Vec3) -> UnitVec3 {
-        return (at - self.center).unit()
-    }
-
-    #[inline]
-    fn is_hit_by(&self, ray: Ray) -> Option<f32> {
+    fn hit(&self, ray: Ray, t_min: f32, t_max: f32) -> Option<Hit> {
         let dist = ray.origin - self.center;
         let alpha = ray.direction * dist;
         let delta = (alpha * alpha) - (dist.sq_norm() - (self.radius * self.radius));
+        if delta < 0.0 { return None }
 
         let alpha = -alpha;
         let beta = f32::sqrt(delta);
 
-        let time = match alpha.partial_cmp(&beta)? {
-            Ordering::Equal => 0.0,
-            Ordering::Greater => alpha - beta,
-            Ordering::Less => match alpha + beta {
-                x if x < 0.0 => return None,
-                x => x,
-            },
-        };
+        let time = [alpha - beta, alpha + beta]
+            .into_iter()
+            .find(|t| (t_min..=t_max).contains(t))?;
+
+        let normal = ((ray.position_at(time) - self.center) / self.radius).unit();
+        let [x, y, z] = normal.to_array();
 
-        return Some(time);
+        // u wraps around the y axis, v goes from the bottom pole to the top one
+        let uv = Vec2::new(
+            0.5 * (f32::atan2(z, x) + PI) * FRAC_1_PI,
+            f32::asin(y.clamp(-1.0, 1.0)) * FRAC_1_PI + 0.5,
+        );
+
+        return Some(Hit::new(ray, time, normal, uv, Vec3::new(-z, 0.0, x)));
+    }
+
+    #[inline]
+    fn bounds(&self) -> Aabb {
+        let radius = Vec3::splat(self.radius.abs());
+        return Aabb::new(self.center - radius, self.center + radius)
     }
 }
 
@@ -50,8 +56,11 @@ fn test_sphere_hit() {
     let center = Vec3::new(0.0, 0.0, 0.0);
     let sphere = Sphere::new(center, 1.0);
     let ray = Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0).unit());
-    let hit = sphere.is_hit_by(ray);
+    let hit = sphere.hit(ray, 0.0, f32::INFINITY);
 
     println!("{hit:?}");
-    assert_eq!(hit.unwrap(), 4.0);
+    let hit = hit.unwrap();
+    assert_eq!(hit.t, 4.0);
+    assert_eq!(hit.normal, Vec3::new(0.0, 0.0, -1.0).unit());
+    assert!(hit.front_face);
 }
@@ -0,0 +1,231 @@
+use super::{Hit, Object, Ray};
+use crate::math::{Aabb, UnitVec3, Vec2, Vec3};
+use std::ops::{Add, Mul};
+
+/// Triangle with optional per-vertex normals and uv coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Triangle {
+    pub vertices: [Vec3; 3],
+    /// Vertex normals used for smooth shading. The flat geometric normal is used if missing.
+    pub normals: Option<[UnitVec3; 3]>,
+    /// Vertex uv coordinates. Defaults to `(0, 0)`, `(1, 0)` and `(1, 1)` if missing.
+    pub uvs: Option<[Vec2; 3]>,
+    /// Vertex colors, interpolated into [`Hit::color`]
+    pub colors: Option<[Vec3; 3]>,
+}
+
+/// Distance and barycentric coordinates of a ray-triangle intersection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TriangleHit {
+    pub t: f32,
+    /// Weights of each of the triangle's vertices, adding up to one
+    pub barycentric: [f32; 3],
+}
+
+impl Triangle {
+    #[inline]
+    pub const fn new(a: Vec3, b: Vec3, c: Vec3) -> Self {
+        return Self {
+            vertices: [a, b, c],
+            normals: None,
+            uvs: None,
+            colors: None,
+        };
+    }
+
+    #[inline]
+    pub const fn with_normals(self, normals: [UnitVec3; 3]) -> Self {
+        return Self {
+            normals: Some(normals),
+            ..self
+        };
+    }
+
+    #[inline]
+    pub const fn with_uvs(self, uvs: [Vec2; 3]) -> Self {
+        return Self {
+            uvs: Some(uvs),
+            ..self
+        };
+    }
+
+    #[inline]
+    pub const fn with_colors(self, colors: [Vec3; 3]) -> Self {
+        return Self {
+            colors: Some(colors),
+            ..self
+        };
+    }
+
+    /// Normal of the triangle's plane, following counter-clockwise winding order
+    #[inline]
+    pub fn geometric_normal(&self) -> UnitVec3 {
+        let [a, b, c] = self.vertices;
+        return (b - a).cross(c - a).unit();
+    }
+
+    /// Watertight ray-triangle intersection, which never lets a ray slip between
+    /// two triangles sharing an edge.
+    // https://jcgt.org/published/0002/01/05/paper.pdf
+    pub fn intersect(&self, ray: Ray, t_min: f32, t_max: f32) -> Option<TriangleHit> {
+        let direction = ray.direction.to_array();
+
+        // permute the axes so that the ray's dominant direction becomes z
+        let kz = match direction.map(f32::abs) {
+            [x, y, z] if x >= y && x >= z => 0,
+            [_, y, z] if y >= z => 1,
+            _ => 2,
+        };
+        let (mut kx, mut ky) = ((kz + 1) % 3, (kz + 2) % 3);
+        if direction[kz] < 0.0 {
+            core::mem::swap(&mut kx, &mut ky);
+        }
+
+        // shear the vertices so that the ray points along +z
+        let sx = direction[kx] / direction[kz];
+        let sy = direction[ky] / direction[kz];
+        let sz = direction[kz].recip();
+
+        let [a, b, c] = self.vertices.map(|v| (v - ray.origin).to_array());
+        let [ax, bx, cx] = [a, b, c].map(|v| v[kx] - sx * v[kz]);
+        let [ay, by, cy] = [a, b, c].map(|v| v[ky] - sy * v[kz]);
+
+        let mut u = cx * by - cy * bx;
+        let mut v = ax * cy - ay * cx;
+        let mut w = bx * ay - by * ax;
+
+        // fall back to double precision on edges
+        if u == 0.0 || v == 0.0 || w == 0.0 {
+            let [ax, bx, cx, ay, by, cy] = [ax, bx, cx, ay, by, cy].map(f64::from);
+            u = (cx * by - cy * bx) as f32;
+            v = (ax * cy - ay * cx) as f32;
+            w = (bx * ay - by * ax) as f32;
+        }
+
+        if (u < 0.0 || v < 0.0 || w < 0.0) && (u > 0.0 || v > 0.0 || w > 0.0) {
+            return None;
+        }
+
+        let det = u + v + w;
+        if det == 0.0 {
+            return None;
+        }
+
+        let [az, bz, cz] = [a, b, c].map(|v| sz * v[kz]);
+        let t = (u * az + v * bz + w * cz) / det;
+        if !(t_min..=t_max).contains(&t) {
+            return None;
+        }
+
+        return Some(TriangleHit {
+            t,
+            barycentric: [u / det, v / det, w / det],
+        });
+    }
+
+    /// Builds the full hit record of a previous intersection with this triangle
+    pub fn hit_info(&self, ray: Ray, hit: TriangleHit) -> Hit {
+        let mut geometric_normal = self.geometric_normal();
+        let shading_normal = match self.normals {
+            Some(normals) => {
+                let normal = hit.interpolate(normals.map(UnitVec3::to_vec)).unit();
+                // keep both normals on the same hemisphere, so winding order doesn't matter
+                if normal * geometric_normal < 0.0 {
+                    geometric_normal = -geometric_normal;
+                }
+                normal
+            }
+            None => geometric_normal,
+        };
+
+        let uvs = self.uvs.unwrap_or([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+        ]);
+
+        // tangent along the direction of increasing u
+        let [a, b, c] = self.vertices;
+        let (duv02, duv12) = (uvs[0] - uvs[2], uvs[1] - uvs[2]);
+        let det = duv02.x() * duv12.y() - duv02.y() * duv12.x();
+        let tangent = match det == 0.0 {
+            true => Vec3::ZERO,
+            false => (duv12.y() * (a - c) - duv02.y() * (b - c)) / det,
+        };
+
+        let mut result = Hit::with_shading_normal(
+            ray,
+            hit.t,
+            geometric_normal,
+            shading_normal,
+            hit.interpolate(uvs),
+            tangent,
+        );
+
+        result.color = self.colors.map(|colors| hit.interpolate(colors));
+        return result;
+    }
+}
+
+impl TriangleHit {
+    /// Interpolates per-vertex values at the intersection point
+    #[inline]
+    pub fn interpolate<T: Mul<f32, Output = T> + Add<Output = T>>(self, [a, b, c]: [T; 3]) -> T {
+        let [u, v, w] = self.barycentric;
+        return a * u + b * v + c * w;
+    }
+}
+
+impl Object for Triangle {
+    #[inline]
+    fn hit(&self, ray: Ray, t_min: f32, t_max: f32) -> Option<Hit> {
+        let hit = self.intersect(ray, t_min, t_max)?;
+        return Some(self.hit_info(ray, hit));
+    }
+
+    #[inline]
+    fn bounds(&self) -> Aabb {
+        return Aabb::from_points(self.vertices);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Triangle;
+    use crate::{math::Vec3, object::Ray};
+
+    #[test]
+    fn test_triangle_hit() {
+        let triangle = Triangle::new(
+            Vec3::new(-1.0, -1.0, -2.0),
+            Vec3::new(1.0, -1.0, -2.0),
+            Vec3::new(0.0, 1.0, -2.0),
+        );
+
+        let ray = Ray::new(Vec3::ZERO, Vec3::new(0.0, 0.0, -1.0).unit());
+        let hit = triangle.intersect(ray, 0.0, f32::INFINITY).unwrap();
+        assert_eq!(hit.t, 2.0);
+        assert_eq!(hit.barycentric, [0.25, 0.25, 0.5]);
+        assert_eq!(hit.interpolate(triangle.vertices), Vec3::new(0.0, 0.0, -2.0));
+
+        let ray = Ray::new(Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0).unit());
+        assert!(triangle.intersect(ray, 0.0, f32::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_shared_edge_is_watertight() {
+        // two triangles sharing the diagonal of the unit square
+        let a = Triangle::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 0.0));
+        let b = Triangle::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+
+        for i in 1..100 {
+            let x = i as f32 / 100.0;
+            let direction = Vec3::new(0.1, -0.3, -1.0).unit();
+            let ray = Ray::new(Vec3::new(x, x, 0.0) - direction, direction);
+            assert!(
+                a.intersect(ray, 0.0, f32::INFINITY).is_some() || b.intersect(ray, 0.0, f32::INFINITY).is_some(),
+                "ray through {x} slipped between the triangles"
+            );
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::math::Vec2;
+
+/// Small and fast pseudo-random number generator, deterministic given its seed
+// https://www.pcg-random.org/download.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    const MULTIPLIER: u64 = 6364136223846793005;
+
+    /// Creates a generator from a seed and a stream. Generators with different streams
+    /// produce independent sequences, even if they share the same seed.
+    #[inline]
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut this = Self {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        this.next_u32();
+        this.state = this.state.wrapping_add(seed);
+        this.next_u32();
+        return this;
+    }
+
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(Self::MULTIPLIER).wrapping_add(self.increment);
+
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+        return xorshifted.rotate_right(rot);
+    }
+
+    /// Uniformly distributed value in `[0, 1)`
+    #[inline]
+    pub fn next_f32(&mut self) -> f32 {
+        // the 24 most significant bits fit exactly in the mantissa
+        return (self.next_u32() >> 8) as f32 * (1.0 / (1u32 << 24) as f32);
+    }
+
+    /// Uniformly distributed point in `[0, 1)²`
+    #[inline]
+    pub fn next_vec2(&mut self) -> Vec2 {
+        let x = self.next_f32();
+        return Vec2::new(x, self.next_f32());
+    }
+}
+
+/// Scrambles the bits of `x`, so that similar inputs give uncorrelated outputs
+// https://github.com/aappleby/smhasher/wiki/MurmurHash3 (64-bit finalizer)
+#[inline]
+pub fn mix_bits(mut x: u64) -> u64 {
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xff51afd7ed558ccd);
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xc4ceb9fe1a85ec53);
+    x ^= x >> 33;
+    return x;
+}
@@ -1,14 +1,21 @@
-use image::Rgb;
+use image::RgbImage;
 use std::borrow::Borrow;
 
 use crate::{
-    display::Framebuffer,
-    element::{Element, ReflectInfo},
+    bvh::Bvh,
+    display::{Framebuffer, HdrImage},
+    element::Element,
     light::{DynLight, Light},
-    math::Vec3,
-    object::{DynObject, Object, Ray},
+    material::{Material, ShadingFrame},
+    math::{UnitVec3, Vec3},
+    object::{DynObject, Hit, Object, Ray},
+    sampler::SampleStream,
 };
 
+/// Fraction of the distance to a light that shadow rays stop short of, so that the
+/// light's own geometry doesn't occlude it
+const SHADOW_EPSILON: f32 = 1e-4;
+
 pub struct Renderer<E, L> {
     frame: Framebuffer,
     pub elements: E,
@@ -29,55 +36,246 @@ where
         };
     }
 
-    pub fn render(&mut self, depth: usize) -> anyhow::Result<()>
+    #[inline]
+    pub fn frame(&self) -> &Framebuffer {
+        return &self.frame;
+    }
+
+    #[inline]
+    pub fn frame_mut(&mut self) -> &mut Framebuffer {
+        return &mut self.frame;
+    }
+
+    /// Renders the scene, returning its linear radiance.
+    ///
+    /// The image is also kept in the framebuffer until the next render, to be converted or saved from there.
+    pub fn render(&mut self, depth: usize) -> HdrImage
     where
         E: Send + Sync,
     {
-        let limit = depth - 1;
+        self.frame.clear();
         let elements: &[Element<DynObject>] = self.elements.borrow();
         let lights: &[DynLight] = self.lights.borrow();
+        let scene = Scene::new(elements);
 
-        self.frame.update(core::convert::identity, |pos, _| {
-            let mut prev_info = ReflectInfo {
-                color: Vec3::ZERO,
-                ray: Ray::new(Vec3::ZERO, pos.unit()),
-            };
-
-            for i in 0..depth {
-                let mut result = None;
-                for element in elements.iter() {
-                    match (element.object.is_hit_by(prev_info.ray), result) {
-                        (Some(t), None) => result = Some((element as &Element<DynObject>, t)),
-                        (Some(t), Some((_, prev))) if t < prev => result = Some((element, t)),
-                        _ => {}
-                    }
+        self.frame.update(|_| (), |ray, stream, _| scene.trace(ray, lights, depth, stream));
+
+        return self.frame.pixels().clone();
+    }
+
+    /// Renders the scene, returning it with 8 bits per channel through the framebuffer's display transform
+    pub fn render_rgb8(&mut self, depth: usize) -> RgbImage
+    where
+        E: Send + Sync,
+    {
+        self.render(depth);
+        return self.frame.to_rgb8();
+    }
+}
+
+/// Bounding volume hierarchy over the renderer's elements
+struct Scene<'e, 'a> {
+    elements: &'e [Element<DynObject<'a>>],
+    bvh: Bvh,
+    /// Index of the element behind each of the hierarchy's primitives
+    bounded: Vec<usize>,
+    /// Elements that can't be bounded (i.e. infinite planes), which are tested against every ray.
+    /// Elements with empty bounds (i.e. meshes without triangles) can't be hit, and are left out.
+    unbounded: Vec<usize>,
+}
+
+impl<'e, 'a> Scene<'e, 'a> {
+    fn new(elements: &'e [Element<DynObject<'a>>]) -> Self {
+        let mut bounds = Vec::with_capacity(elements.len());
+        let mut bounded = Vec::with_capacity(elements.len());
+        let mut unbounded = Vec::new();
+
+        for (i, element) in elements.iter().enumerate() {
+            let aabb = element.object.bounds();
+            if aabb.is_empty() {
+                continue;
+            }
+            match aabb.is_finite() {
+                true => {
+                    bounds.push(aabb);
+                    bounded.push(i)
                 }
+                false => unbounded.push(i),
+            }
+        }
+
+        return Self {
+            elements,
+            bvh: Bvh::new(&bounds),
+            bounded,
+            unbounded,
+        };
+    }
+
+    /// Returns the closest element hit by `ray` within `[t_min, t_max]`
+    fn hit(&self, ray: Ray, t_min: f32, t_max: f32) -> Option<(&'e Element<DynObject<'a>>, Hit)> {
+        let mut result = self.bvh.traverse(ray, t_min, t_max, |i, t_max| {
+            let element = &self.elements[self.bounded[i]];
+            let hit = element.object.hit(ray, t_min, t_max)?;
+            return Some(((element, hit), hit.t));
+        });
 
-                if let Some((element, t)) = result {
-                    let new_origin = prev_info.ray.position_at(t);
-                    
-                    let mut color = prev_info.color;
-                    for light in lights.iter() {
-                        if let Some(c) = Light::hits(light as &DynLight, new_origin) {
-                            color += c
+        for &i in self.unbounded.iter() {
+            let element = &self.elements[i];
+            let t_max = result.as_ref().map_or(t_max, |(_, hit): &(_, Hit)| hit.t);
+            if let Some(hit) = element.object.hit(ray, t_min, t_max) {
+                result = Some((element, hit));
+            }
+        }
+
+        return result;
+    }
+
+    /// Radiance carried back along `ray`, following at most `depth` specular bounces.
+    ///
+    /// Every hit is lit directly by the lights, while only the specular lobes of the materials
+    /// are followed (as in Whitted-style ray tracing).
+    fn trace(&self, mut ray: Ray, lights: &[DynLight], depth: usize, stream: &mut SampleStream) -> Vec3 {
+        let mut radiance = Vec3::ZERO;
+        let mut throughput = Vec3::splat(1.0);
+
+        for _ in 0..depth {
+            let Some((element, hit)) = self.hit(ray, 0.0, f32::INFINITY) else { break };
+            let material = &element.material;
+            let frame = ShadingFrame::new(&hit);
+            let wo = frame.to_local(-ray.direction.to_vec());
+
+            let mut color = material.emitted(&hit, wo);
+            for light in lights.iter() {
+                let Some(sample) = Light::sample(light, hit.position) else { continue };
+                match sample.direction {
+                    Some(direction) => {
+                        if self.is_occluded(&hit, direction, sample.distance) {
+                            continue;
                         }
+                        let wi = frame.to_local(direction.to_vec());
+                        color += sample.color.wide_mul(material.eval(&hit, wo, wi)) * wi.z().abs();
                     }
-                    
-                    prev_info.color = color.wide_mul(element.material.reflectiveness); // todo
-                    if i < limit {
-                        let normal = element.object.normal(prev_info.ray.origin);
-                        prev_info.ray.direction = prev_info.ray.reflect(normal);
-                        prev_info.ray.origin = new_origin
-                    }
+                    None => color += sample.color.wide_mul(material.albedo(&hit, wo)),
                 }
             }
+            radiance += throughput.wide_mul(color);
 
-            let color = 255. * prev_info.color;
-            Rgb(color.to_array().map(|x| x as u8))
-        });
+            let Some(sample) = material.sample(&hit, wo, stream.next_2d()) else { break };
+            if !sample.specular || !(sample.pdf > 0.0) {
+                break;
+            }
 
-        self.frame.display()?;
-        self.frame.clear();
-        return Ok(());
+            throughput = throughput.wide_mul(sample.weight());
+            ray = Ray::spawn(&hit, frame.to_world(sample.wi).unit());
+        }
+
+        return radiance;
     }
+
+    /// Checks if any element lies between `hit` and the point `distance` units away in `direction`
+    fn is_occluded(&self, hit: &Hit, direction: UnitVec3, distance: f32) -> bool {
+        let ray = Ray::spawn(hit, direction);
+        let t_max = distance * (1.0 - SHADOW_EPSILON);
+        let occludes = |i: usize| self.elements[i].object.hit(ray, 0.0, t_max).is_some();
+
+        return self.unbounded.iter().any(|&i| occludes(i))
+            || self.bvh.any(ray, 0.0, t_max, |i| occludes(self.bounded[i]));
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_render_to_memory() {
+    use crate::{display::Camera, light::Ambient, material::Lambertian, object::sphere::Sphere};
+    use std::sync::Arc;
+
+    let frame = Framebuffer::new(8, 8, Camera::default()).unwrap();
+    let elements = vec![Element::new_unzise(
+        Sphere::new(Vec3::new(0.0, 0.0, -3.0), 1.0),
+        Arc::new(Lambertian::new(Vec3::splat(0.5))),
+    )];
+    let mut renderer = Renderer::new(frame, elements, [Ambient::new_unsize(Vec3::splat(2.0))]);
+
+    let image = renderer.render(1);
+    assert_eq!(image.dimensions(), (8, 8));
+    assert_eq!(image.get_pixel(4, 4).0, [1.0, 1.0, 1.0, 1.0]);
+    assert_eq!(image.get_pixel(0, 0).0, [0.0, 0.0, 0.0, 1.0]);
+
+    // rendering again gives the same image, instead of accumulating over the previous one
+    assert_eq!(renderer.render(1), image);
+    assert_eq!(renderer.render_rgb8(1).get_pixel(4, 4).0, [255; 3]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_empty_elements() {
+    use crate::{
+        material::Lambertian,
+        object::{mesh::TriangleMesh, plane::Plane},
+    };
+    use std::sync::Arc;
+
+    let material = Arc::new(Lambertian::new(Vec3::splat(0.5)));
+    let elements = vec![
+        Element::new_unzise(TriangleMesh::new(Vec::new(), Vec::new()), material.clone()),
+        Element::new_unzise(Plane::new(Vec3::ZERO, Vec3::new(0.0, 1.0, 0.0).unit()), material),
+    ];
+
+    // the empty mesh is skipped, rather than tested against every ray
+    let scene = Scene::new(&elements);
+    assert_eq!((scene.bounded.as_slice(), scene.unbounded.as_slice()), (&[][..], &[1][..]));
+}
+
+/// Times the hierarchy against testing every element, which `cargo test --release -- --ignored` reports
+#[cfg(test)]
+#[test]
+#[ignore]
+fn test_bvh_speedup() {
+    use crate::{material::Lambertian, object::sphere::Sphere, random::Pcg32};
+    use std::{sync::Arc, time::Instant};
+
+    let mut rng = Pcg32::new(9, 0);
+    let material = Arc::new(Lambertian::new(Vec3::splat(0.5)));
+    let elements = (0..100_000)
+        .map(|_| {
+            let center = Vec3::new(rng.next_f32(), rng.next_f32(), rng.next_f32()) * 200.0 - Vec3::splat(100.0);
+            Element::new_unzise(Sphere::new(center, 0.2 + 0.3 * rng.next_f32()), material.clone())
+        })
+        .collect::<Vec<_>>();
+    let rays = (0..2_000)
+        .map(|_| {
+            let direction = Vec3::new(rng.next_f32(), rng.next_f32(), rng.next_f32()) - Vec3::splat(0.5);
+            Ray::new(Vec3::ZERO, direction.unit())
+        })
+        .collect::<Vec<_>>();
+
+    let start = Instant::now();
+    let scene = Scene::new(&elements);
+    let build = start.elapsed();
+
+    let start = Instant::now();
+    let found = rays.iter().map(|&ray| scene.hit(ray, 0.0, f32::INFINITY).map(|(_, hit)| hit.t)).collect::<Vec<_>>();
+    let bvh = start.elapsed();
+
+    let start = Instant::now();
+    let expected = rays
+        .iter()
+        .map(|&ray| {
+            elements
+                .iter()
+                .filter_map(|x| x.object.hit(ray, 0.0, f32::INFINITY))
+                .map(|x| x.t)
+                .min_by(f32::total_cmp)
+        })
+        .collect::<Vec<_>>();
+    let linear = start.elapsed();
+
+    assert_eq!(found, expected);
+    println!(
+        "{} spheres, {} rays: built in {build:?}, traversed in {bvh:?} against {linear:?} linearly ({:.0}x)",
+        elements.len(),
+        rays.len(),
+        linear.as_secs_f64() / bvh.as_secs_f64()
+    );
 }
@@ -0,0 +1,106 @@
+use super::{hash, Sampler, Sobol, ONE_MINUS_EPSILON};
+use crate::math::Vec2;
+
+/// Bases of the radical inverses, two per dimension
+const PRIMES: [u32; 64] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53,
+    59, 61, 67, 71, 73, 79, 83, 89, 97, 101, 103, 107, 109, 113, 127, 131,
+    137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193, 197, 199, 211, 223,
+    227, 229, 233, 239, 241, 251, 257, 263, 269, 271, 277, 281, 283, 293, 307, 311,
+];
+
+/// Low-discrepancy samples from the Halton sequence, with the `i`-th dimension using
+/// the radical inverses in the `2i`-th and `2i+1`-th prime bases. Dimensions past the
+/// table of bases are padded from a [`Sobol`] sampler with the same seed.
+///
+/// Every pixel uses the same points, offset by a random toroidal shift (Cranley-Patterson
+/// rotation) so that neighbouring pixels aren't correlated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Halton {
+    pub samples_per_pixel: u32,
+    pub seed: u64,
+}
+
+impl Halton {
+    #[inline]
+    pub const fn new(samples_per_pixel: u32, seed: u64) -> Self {
+        return Self {
+            samples_per_pixel,
+            seed,
+        };
+    }
+}
+
+impl Sampler for Halton {
+    #[inline]
+    fn samples_per_pixel(&self) -> u32 {
+        return self.samples_per_pixel;
+    }
+
+    #[inline]
+    fn sample_2d(&self, pixel: [u32; 2], index: u32, dimension: u32) -> Vec2 {
+        // reusing the bases would repeat the same points, only shifted, so switch samplers instead
+        let bases = 2 * dimension as usize;
+        if bases >= PRIMES.len() {
+            return Sobol::new(self.samples_per_pixel, self.seed).sample_2d(pixel, index, dimension);
+        }
+        let shift = hash(self.seed, pixel, dimension);
+
+        let [x, y] = [0, 1].map(|i| {
+            let offset = super::to_unit((shift >> (32 * i)) as u32);
+            let x = radical_inverse(index, PRIMES[bases + i]) + offset;
+            f32::min(x - x.floor(), ONE_MINUS_EPSILON)
+        });
+        return Vec2::new(x, y);
+    }
+}
+
+/// Mirrors the digits of `index` in the given base around the radix point
+#[inline]
+fn radical_inverse(mut index: u32, base: u32) -> f32 {
+    let inv_base = 1.0 / base as f64;
+    let mut result = 0.0;
+    let mut scale = inv_base;
+    while index > 0 {
+        result += (index % base) as f64 * scale;
+        index /= base;
+        scale *= inv_base;
+    }
+    return result as f32;
+}
+
+#[cfg(test)]
+#[test]
+fn test_radical_inverse() {
+    assert_eq!([1, 2, 3, 4].map(|i| radical_inverse(i, 2)), [0.5, 0.25, 0.75, 0.125]);
+    assert_eq!([1, 2, 3, 4].map(|i| radical_inverse(i, 3)), [1.0 / 3.0, 2.0 / 3.0, 1.0 / 9.0, 4.0 / 9.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_high_dimensions() {
+    let halton = Halton::new(16, 7);
+    let points = |dimension: u32| (0..16).map(|i| halton.sample_2d([3, 5], i, dimension)).collect::<Vec<_>>();
+
+    // no two dimensions share their bases, which would give them the same points up to a shift
+    let dimensions = PRIMES.len() as u32 / 2;
+    for dimension in 0..dimensions {
+        let shift = |a: Vec2, b: Vec2| {
+            let d = b - a;
+            Vec2::new(d.x() - d.x().floor(), d.y() - d.y().floor())
+        };
+        let (a, b) = (points(dimension), points(dimension + dimensions));
+        let shifts = a.iter().zip(b.iter()).map(|(&a, &b)| shift(a, b)).collect::<Vec<_>>();
+        assert!(shifts.iter().any(|&x| (x - shifts[0]).norm() > 1e-3), "{dimension}");
+    }
+
+    // and the ones past the table are still well distributed
+    for dimension in dimensions..dimensions + 4 {
+        let mut strata = [false; 16];
+        for x in points(dimension) {
+            assert!((0.0..1.0).contains(&x.x()) && (0.0..1.0).contains(&x.y()));
+            strata[(4.0 * x.y()) as usize * 4 + (4.0 * x.x()) as usize] = true;
+        }
+        assert!(strata.iter().all(|&x| x), "{dimension}");
+    }
+}
@@ -0,0 +1,31 @@
+use super::{hash, Sampler};
+use crate::{math::Vec2, random::Pcg32};
+
+/// Uniformly distributed random samples, with no stratification at all
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Independent {
+    pub samples_per_pixel: u32,
+    pub seed: u64,
+}
+
+impl Independent {
+    #[inline]
+    pub const fn new(samples_per_pixel: u32, seed: u64) -> Self {
+        return Self {
+            samples_per_pixel,
+            seed,
+        };
+    }
+}
+
+impl Sampler for Independent {
+    #[inline]
+    fn samples_per_pixel(&self) -> u32 {
+        return self.samples_per_pixel;
+    }
+
+    #[inline]
+    fn sample_2d(&self, pixel: [u32; 2], index: u32, dimension: u32) -> Vec2 {
+        return Pcg32::new(hash(self.seed, pixel, dimension), index as u64).next_vec2();
+    }
+}
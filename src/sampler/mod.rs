@@ -0,0 +1,187 @@
+flat_mod! { independent, stratified, halton, sobol }
+
+use crate::{math::Vec2, random::mix_bits};
+use std::fmt::Debug;
+
+/// Source of the sample points used to integrate over a pixel (and over the camera's lens).
+///
+/// Samplers are stateless: every value is a function of the pixel, the sample's index within
+/// the pixel and the dimension being sampled, so they are deterministic given their seed and
+/// may be evaluated in any order, from any thread.
+pub trait Sampler: Debug + Send + Sync {
+    /// Number of samples taken for every pixel
+    fn samples_per_pixel(&self) -> u32;
+
+    /// Returns the `dimension`-th pair of values of the `index`-th sample of `pixel`, in `[0, 1)²`.
+    ///
+    /// Dimension 0 is used for the position within the pixel, and dimension 1 for the position on the lens.
+    /// The following ones are handed out by a [`SampleStream`].
+    fn sample_2d(&self, pixel: [u32; 2], index: u32, dimension: u32) -> Vec2;
+}
+
+impl<T: ?Sized + Sampler> Sampler for &T {
+    #[inline]
+    fn samples_per_pixel(&self) -> u32 {
+        return T::samples_per_pixel(self);
+    }
+
+    #[inline]
+    fn sample_2d(&self, pixel: [u32; 2], index: u32, dimension: u32) -> Vec2 {
+        return T::sample_2d(self, pixel, index, dimension);
+    }
+}
+
+impl<T: ?Sized + Sampler> Sampler for Box<T> {
+    #[inline]
+    fn samples_per_pixel(&self) -> u32 {
+        return T::samples_per_pixel(self);
+    }
+
+    #[inline]
+    fn sample_2d(&self, pixel: [u32; 2], index: u32, dimension: u32) -> Vec2 {
+        return T::sample_2d(self, pixel, index, dimension);
+    }
+}
+
+/// The dimensions of a single sample, handed out in order
+#[derive(Debug, Clone, Copy)]
+pub struct SampleStream<'a> {
+    sampler: &'a dyn Sampler,
+    pixel: [u32; 2],
+    index: u32,
+    dimension: u32,
+}
+
+impl<'a> SampleStream<'a> {
+    /// Dimensions used by the camera, which the stream starts after
+    pub const CAMERA_DIMENSIONS: u32 = 2;
+
+    #[inline]
+    pub fn new(sampler: &'a dyn Sampler, pixel: [u32; 2], index: u32) -> Self {
+        return Self {
+            sampler,
+            pixel,
+            index,
+            dimension: Self::CAMERA_DIMENSIONS,
+        };
+    }
+
+    /// Next pair of values of the sample, in `[0, 1)²`
+    #[inline]
+    pub fn next_2d(&mut self) -> Vec2 {
+        let result = self.sampler.sample_2d(self.pixel, self.index, self.dimension);
+        self.dimension += 1;
+        return result;
+    }
+}
+
+/// Hashes the seed with the pixel and dimension being sampled
+#[inline]
+fn hash(seed: u64, pixel: [u32; 2], dimension: u32) -> u64 {
+    let pixel = ((pixel[0] as u64) << 32) | (pixel[1] as u64);
+    return mix_bits(mix_bits(seed ^ mix_bits(pixel)) ^ (dimension as u64));
+}
+
+/// Largest value below one
+const ONE_MINUS_EPSILON: f32 = 1.0 - f32::EPSILON / 2.0;
+
+/// Converts 32 random bits into a value in `[0, 1)`
+#[inline]
+fn to_unit(bits: u32) -> f32 {
+    // the 24 most significant bits fit exactly in the mantissa
+    return (bits >> 8) as f32 * (1.0 / (1u32 << 24) as f32);
+}
+
+/// Returns the `i`-th element of a pseudo-random permutation of `[0, len)`, selected by `seed`
+// "Correlated Multi-Jittered Sampling", Andrew Kensler
+#[inline]
+fn permutation_element(mut i: u32, len: u32, seed: u32) -> u32 {
+    debug_assert!(i < len);
+    let mut w = len - 1;
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+
+    // bijective on [0, w], repeated until the result is in range (cycle walking)
+    loop {
+        i ^= seed;
+        i = i.wrapping_mul(0xe170893d);
+        i ^= seed >> 16;
+        i ^= (i & w) >> 4;
+        i ^= seed >> 8;
+        i = i.wrapping_mul(0x0929eb3f);
+        i ^= seed >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | seed >> 27);
+        i = i.wrapping_mul(0x6935fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dcb303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e501cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860a3df);
+        i &= w;
+        i ^= i >> 5;
+        if i < len {
+            break;
+        }
+    }
+
+    return (i.wrapping_add(seed)) % len;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permutation() {
+        for len in [1, 2, 7, 16, 100] {
+            for seed in [0, 1, 0xdeadbeef] {
+                let mut seen = vec![false; len as usize];
+                for i in 0..len {
+                    let x = permutation_element(i, len, seed) as usize;
+                    assert!(!seen[x]);
+                    seen[x] = true;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_samplers() {
+        let samplers: [Box<dyn Sampler>; 4] = [
+            Box::new(Independent::new(16, 7)),
+            Box::new(Stratified::new(16, 7)),
+            Box::new(Halton::new(16, 7)),
+            Box::new(Sobol::new(16, 7)),
+        ];
+
+        for sampler in samplers.iter() {
+            for dimension in 0..4 {
+                let points = (0..16).map(|i| sampler.sample_2d([3, 5], i, dimension)).collect::<Vec<_>>();
+                assert!(points.iter().flat_map(|x| x.into_array()).all(|x| (0.0..1.0).contains(&x)));
+
+                // deterministic
+                assert_eq!(points[3], sampler.sample_2d([3, 5], 3, dimension));
+                // decorrelated across pixels
+                assert_ne!(points[3], sampler.sample_2d([4, 5], 3, dimension));
+            }
+        }
+
+        // every stratum of a 4x4 grid gets exactly one sample
+        for sampler in [&samplers[1], &samplers[3]] {
+            for dimension in 0..4 {
+                let mut strata = [false; 16];
+                for i in 0..16 {
+                    let x = sampler.sample_2d([3, 5], i, dimension);
+                    let stratum = (4.0 * x.y()) as usize * 4 + (4.0 * x.x()) as usize;
+                    assert!(!strata[stratum], "{sampler:?}");
+                    strata[stratum] = true;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use super::{hash, Sampler};
+use crate::math::Vec2;
+
+/// Low-discrepancy samples from the first two dimensions of the Sobol sequence, scrambled
+/// with Owen's nested uniform scrambling.
+///
+/// Every dimension is padded from an independently shuffled and scrambled copy of the
+/// 2D sequence, which keeps the samples well stratified within each dimension.
+///
+/// Best results are achieved with a power of two samples per pixel.
+// "Practical Hash-based Owen Scrambling", Brent Burley
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sobol {
+    pub samples_per_pixel: u32,
+    pub seed: u64,
+}
+
+impl Sobol {
+    #[inline]
+    pub const fn new(samples_per_pixel: u32, seed: u64) -> Self {
+        return Self {
+            samples_per_pixel,
+            seed,
+        };
+    }
+}
+
+impl Sampler for Sobol {
+    #[inline]
+    fn samples_per_pixel(&self) -> u32 {
+        return self.samples_per_pixel;
+    }
+
+    #[inline]
+    fn sample_2d(&self, pixel: [u32; 2], index: u32, dimension: u32) -> Vec2 {
+        let seed = hash(self.seed, pixel, dimension);
+        let index = owen_scramble(index, seed as u32);
+
+        let x = owen_scramble(index.reverse_bits(), (seed >> 32) as u32);
+        let y = owen_scramble(sobol_1(index), (seed >> 16) as u32 ^ 0x9e3779b9);
+        return Vec2::new(super::to_unit(x), super::to_unit(y));
+    }
+}
+
+/// Second dimension of the Sobol sequence, whose generator matrix is Pascal's triangle modulo two.
+/// The first dimension is the van der Corput sequence, i.e. the index with its bits reversed.
+#[inline]
+fn sobol_1(mut index: u32) -> u32 {
+    let mut result = 0;
+    let mut direction = 1 << 31;
+    while index != 0 {
+        if index & 1 != 0 {
+            result ^= direction;
+        }
+        index >>= 1;
+        direction ^= direction >> 1;
+    }
+    return result;
+}
+
+/// Randomly permutes the bits of `x` so that every bit only depends on the higher ones
+#[inline]
+fn owen_scramble(x: u32, seed: u32) -> u32 {
+    return laine_karras_permutation(x.reverse_bits(), seed).reverse_bits();
+}
+
+/// Hash in which every bit only depends on the lower ones
+#[inline]
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50b47c);
+    x ^= x.wrapping_mul(0xb82f1e52);
+    x ^= x.wrapping_mul(0xc7afe638);
+    x ^= x.wrapping_mul(0x8d22f6e6);
+    return x;
+}
+
+#[cfg(test)]
+#[test]
+fn test_sobol_1() {
+    let expected = [0.0, 0.5, 0.75, 0.25, 0.625, 0.125, 0.375, 0.875];
+    for (i, x) in expected.into_iter().enumerate() {
+        assert_eq!(sobol_1(i as u32) as f64 / (1u64 << 32) as f64, x);
+    }
+}
@@ -0,0 +1,53 @@
+use super::{hash, permutation_element, Sampler, ONE_MINUS_EPSILON};
+use crate::{math::Vec2, random::Pcg32};
+
+/// Jittered samples, with the pixel split in a grid of strata and one random point picked
+/// in each of them. The strata are shuffled independently for every dimension.
+///
+/// If the number of samples isn't a perfect square, the grid has more cells than samples,
+/// and some of them are left empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stratified {
+    pub samples_per_pixel: u32,
+    pub seed: u64,
+}
+
+impl Stratified {
+    #[inline]
+    pub const fn new(samples_per_pixel: u32, seed: u64) -> Self {
+        return Self {
+            samples_per_pixel,
+            seed,
+        };
+    }
+
+    /// Size of the grid of strata
+    #[inline]
+    fn grid(&self) -> (u32, u32) {
+        let n = self.samples_per_pixel.max(1);
+        let width = f32::ceil(f32::sqrt(n as f32)) as u32;
+        return (width, n.div_ceil(width));
+    }
+}
+
+impl Sampler for Stratified {
+    #[inline]
+    fn samples_per_pixel(&self) -> u32 {
+        return self.samples_per_pixel;
+    }
+
+    #[inline]
+    fn sample_2d(&self, pixel: [u32; 2], index: u32, dimension: u32) -> Vec2 {
+        let (width, height) = self.grid();
+        let seed = hash(self.seed, pixel, dimension);
+
+        let stratum = permutation_element(index % (width * height), width * height, seed as u32);
+        let (x, y) = (stratum % width, stratum / width);
+        let jitter = Pcg32::new(seed, index as u64).next_vec2();
+
+        // rounding may push the point onto the upper bound
+        let x = f32::min((x as f32 + jitter.x()) / width as f32, ONE_MINUS_EPSILON);
+        let y = f32::min((y as f32 + jitter.y()) / height as f32, ONE_MINUS_EPSILON);
+        return Vec2::new(x, y);
+    }
+}